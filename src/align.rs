@@ -0,0 +1,144 @@
+use crate::{
+    buffer::{
+        BufferError, BufferErrorReason, FiniteBuffer, PositionedBuffer, Result, SplittableBuffer,
+    },
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+
+#[inline(always)]
+fn padding_len(position: usize, align: usize) -> usize {
+    (align - position % align) % align
+}
+
+/// Skips or zero-fills bytes up to the next `ALIGN` boundary
+///
+/// Boundaries are computed relative to the start of the message, as reported by
+/// `PositionedBuffer`. When `STRICT` is `true`, decoding fails if any of the
+/// padding bytes are non-zero. An `ALIGN` of zero is rejected at compile time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Align<const ALIGN: usize, const STRICT: bool = false>;
+
+impl<const ALIGN: usize, const STRICT: bool> Align<ALIGN, STRICT> {
+    const NON_ZERO: () = assert!(ALIGN != 0, "ALIGN must be non-zero");
+}
+
+impl<B, const ALIGN: usize, const STRICT: bool> Decoder<(), B> for Align<ALIGN, STRICT>
+where
+    B: SplittableBuffer + PositionedBuffer,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<(), B> {
+        let () = Self::NON_ZERO;
+        let len = padding_len(buffer.position(), ALIGN);
+        buffer.checked_split_with(len, |padding| {
+            if STRICT {
+                if let Some(actual) = padding.as_less_safe_slice().iter().find(|b| **b != 0) {
+                    return Err(BufferError::new(
+                        padding,
+                        BufferErrorReason::UnexpectedValue {
+                            expected: 0,
+                            actual: *actual as u128,
+                        },
+                    ));
+                }
+            }
+            Ok(((), padding))
+        })
+    }
+}
+
+impl<B, const ALIGN: usize, const STRICT: bool> Encoder<(), B> for Align<ALIGN, STRICT>
+where
+    B: EncoderBuffer + PositionedBuffer,
+{
+    #[inline(always)]
+    fn encode_into(self, _value: (), buffer: B) -> Result<(), B> {
+        let () = Self::NON_ZERO;
+        let len = padding_len(buffer.position(), ALIGN);
+        let (_, buffer) = buffer.encode_repeated(0u8, len)?;
+        Ok(((), buffer))
+    }
+}
+
+/// A value followed by padding up to the next `ALIGN` boundary
+///
+/// See `Align` for how boundaries and `STRICT` are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Padded<T, const ALIGN: usize, const STRICT: bool = false>(pub T);
+
+impl<T, B, const ALIGN: usize, const STRICT: bool> TypeDecoder<B> for Padded<T, ALIGN, STRICT>
+where
+    T: TypeDecoder<B>,
+    B: SplittableBuffer + PositionedBuffer,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (value, buffer) = buffer.decode()?;
+        let ((), buffer) = buffer.decode_with(Align::<ALIGN, STRICT>)?;
+        Ok((Self(value), buffer))
+    }
+}
+
+impl<T, B, const ALIGN: usize, const STRICT: bool> TypeEncoder<B> for Padded<T, ALIGN, STRICT>
+where
+    T: TypeEncoder<B>,
+    B: EncoderBuffer + PositionedBuffer,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(self.0)?;
+        let (_, buffer) = buffer.encode_with((), Align::<ALIGN, STRICT>)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, T, B, const ALIGN: usize, const STRICT: bool> TypeEncoder<B>
+    for &'a Padded<T, ALIGN, STRICT>
+where
+    &'a T: TypeEncoder<B>,
+    B: EncoderBuffer + PositionedBuffer,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(&self.0)?;
+        let (_, buffer) = buffer.encode_with((), Align::<ALIGN, STRICT>)?;
+        Ok(((), buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::Positioned;
+
+    #[test]
+    fn decode_padded_test() {
+        let buffer = Positioned::new(&[1, 0, 0, 0, 0, 2, 0, 0][..]);
+        let (value, buffer) = buffer.decode::<Padded<u8, 4>>().unwrap();
+        assert_eq!(value.0, 1);
+        assert_eq!(buffer.position(), 4);
+        let (value, buffer) = buffer.decode::<Padded<u16, 4>>().unwrap();
+        assert_eq!(value.0, 2);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn decode_strict_padding_test() {
+        let buffer = Positioned::new(&[1, 0, 3, 0][..]);
+        let (_, buffer) = buffer.decode::<u8>().unwrap();
+        assert!(buffer.decode_with(Align::<4, true>).is_err());
+        assert!(buffer.decode_with(Align::<4>).is_ok());
+    }
+
+    #[test]
+    fn encode_padded_test() {
+        let mut out = [0xff; 8];
+        let buffer = Positioned::new(&mut out[..]);
+        let (_, buffer) = buffer.encode(Padded::<_, 4>(1u8)).unwrap();
+        assert_eq!(buffer.position(), 4);
+        let (_, buffer) = buffer.encode(Padded::<_, 4>(2u16)).unwrap();
+        assert_eq!(buffer.position(), 8);
+        assert_eq!(out, [1, 0, 0, 0, 0, 2, 0, 0]);
+    }
+}
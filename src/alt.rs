@@ -0,0 +1,213 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, LookaheadBuffer, Result},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, TypeEncoder},
+};
+use core::marker::PhantomData;
+
+/// Runs `f` against a lookahead of `buffer`, returning the value and the number of bytes
+/// it consumed without advancing `buffer`
+#[inline(always)]
+fn attempt<T, B, F>(buffer: &B, f: F) -> core::result::Result<(T, usize), BufferError<()>>
+where
+    B: FiniteBuffer,
+    F: FnOnce(LookaheadBuffer) -> Result<T, LookaheadBuffer>,
+{
+    match f(buffer.lookahead()) {
+        Ok((value, rest)) => Ok((value, buffer.len() - rest.len())),
+        Err(err) => Err(err.with_buffer(())),
+    }
+}
+
+#[inline(always)]
+fn advance<T, B: FiniteBuffer>(buffer: B, value: T, consumed: usize) -> Result<T, B> {
+    let (_, buffer) = buffer.checked_split(consumed)?;
+    Ok((value, buffer))
+}
+
+/// Tries each decoder in a tuple in order, returning the first success
+///
+/// Failed alternatives do not consume any bytes. If all of them fail, the error
+/// from the last alternative is returned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Alt<D>(pub D);
+
+macro_rules! impl_alt {
+    ($($D:ident),*) => {
+        impl_alt!([$($D,)*], []);
+    };
+    ([], [$($prev:ident),*]) => {
+        // done
+    };
+    ([$current:ident, $($rest:ident,)*], [$($prev:ident),*]) => {
+        impl<
+            _T,
+            _B: FiniteBuffer,
+            $($prev: for<'b> Decoder<_T, LookaheadBuffer<'b>>,)*
+            $current: for<'b> Decoder<_T, LookaheadBuffer<'b>>
+        > Decoder<_T, _B> for Alt<($($prev,)* $current,)> {
+            #[inline(always)]
+            fn decode_from(self, buffer: _B) -> Result<_T, _B> {
+                #![allow(non_snake_case)]
+                let ($($prev,)* $current,) = self.0;
+                $(
+                    if let Ok((value, consumed)) = attempt(&buffer, |b| $prev.decode_from(b)) {
+                        return advance(buffer, value, consumed);
+                    }
+                )*
+                match attempt(&buffer, |b| $current.decode_from(b)) {
+                    Ok((value, consumed)) => advance(buffer, value, consumed),
+                    Err(err) => Err(err.with_buffer(buffer)),
+                }
+            }
+        }
+
+        impl_alt!([$($rest,)*], [$($prev,)* $current]);
+    };
+}
+
+impl_alt!(A, B, C, D, E, F, G, H);
+
+/// An optional value that is absent if it fails to decode
+///
+/// Unlike `Option<T>`, a failed decode is rolled back rather than returned as an error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Opt<T>(pub Option<T>);
+
+impl<T, B> TypeDecoder<B> for Opt<T>
+where
+    B: FiniteBuffer,
+    T: for<'b> TypeDecoder<LookaheadBuffer<'b>>,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        match attempt(&buffer, |b| b.decode::<T>()) {
+            Ok((value, consumed)) => advance(buffer, Self(Some(value)), consumed),
+            Err(_) => Ok((Self(None), buffer)),
+        }
+    }
+}
+
+impl<T: TypeEncoder<B>, B: EncoderBuffer> TypeEncoder<B> for Opt<T> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, T, B: EncoderBuffer> TypeEncoder<B> for &'a Opt<T>
+where
+    &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(&self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+/// A value decoded without consuming any bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Peeked<T>(pub T);
+
+impl<T, B> TypeDecoder<B> for Peeked<T>
+where
+    B: FiniteBuffer,
+    T: for<'b> TypeDecoder<LookaheadBuffer<'b>>,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        match attempt(&buffer, |b| b.decode::<T>()) {
+            Ok((value, _)) => Ok((Self(value), buffer)),
+            Err(err) => Err(err.with_buffer(buffer)),
+        }
+    }
+}
+
+/// Decodes a `T` and checks it against a predicate
+///
+/// The buffer is left untouched if the value fails to decode or is rejected.
+pub struct Verify<T, F> {
+    check: F,
+    value: PhantomData<T>,
+}
+
+impl<T, F: FnOnce(&T) -> bool> Verify<T, F> {
+    #[inline(always)]
+    pub fn new(check: F) -> Self {
+        Self {
+            check,
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T, F, B> Decoder<T, B> for Verify<T, F>
+where
+    B: FiniteBuffer,
+    T: for<'b> TypeDecoder<LookaheadBuffer<'b>>,
+    F: FnOnce(&T) -> bool,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<T, B> {
+        match attempt(&buffer, |b| b.decode::<T>()) {
+            Ok((value, consumed)) if (self.check)(&value) => advance(buffer, value, consumed),
+            Ok(_) => Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "verification failed",
+                },
+            )),
+            Err(err) => Err(err.with_buffer(buffer)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alt_test() {
+        let decoder = || Alt((Verify::new(|v: &u8| *v == 1), Verify::new(|v: &u8| *v == 2)));
+
+        let (value, buffer) = (&[2u8, 3][..]).decode_with(decoder()).unwrap();
+        assert_eq!(value, 2);
+        assert_eq!(buffer, &[3]);
+
+        let err = (&[3u8][..]).decode_with(decoder()).unwrap_err();
+        assert_eq!(err.buffer, &[3]);
+        assert_eq!(
+            err.reason,
+            BufferErrorReason::InvalidValue {
+                message: "verification failed"
+            }
+        );
+    }
+
+    #[test]
+    fn opt_test() {
+        let (Opt(value), buffer) = (&[1u8, 2, 3][..]).decode::<Opt<u16>>().unwrap();
+        assert_eq!(value, Some(0x0102));
+        assert_eq!(buffer, &[3]);
+
+        let (Opt(value), buffer) = buffer.decode::<Opt<u16>>().unwrap();
+        assert_eq!(value, None);
+        assert_eq!(buffer, &[3]);
+
+        let mut out = [0u8; 2];
+        (&mut out[..]).encode(Opt(Some(7u16))).unwrap();
+        assert_eq!(out, [0, 7]);
+    }
+
+    #[test]
+    fn peeked_test() {
+        let (Peeked(value), buffer) = (&[1u8, 2][..]).decode::<Peeked<u8>>().unwrap();
+        assert_eq!(value, 1);
+        assert_eq!(buffer, &[1, 2]);
+
+        let err = (&[1u8][..]).decode::<Peeked<u16>>().unwrap_err();
+        assert_eq!(err.buffer, &[1]);
+    }
+}
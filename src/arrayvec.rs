@@ -0,0 +1,21 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
+    count::{encode_items, CountPrefix},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+use arrayvec::ArrayVec;
+use core::convert::{TryFrom, TryInto};
+
+impl_fixed_vec!(ArrayVec, try_push);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        len::LenPrefix,
+        limit::{Limited, Limits},
+    };
+
+    impl_fixed_vec!(@tests ArrayVec);
+}
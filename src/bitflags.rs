@@ -0,0 +1,161 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, Result, SplittableBuffer},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+use bitflags::Flags;
+
+/// Rejects any bits that do not correspond to a known flag
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reject;
+
+/// Silently discards any bits that do not correspond to a known flag
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Truncate;
+
+/// Preserves unknown bits so the value round-trips unchanged
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Retain;
+
+const UNKNOWN_BITS: BufferErrorReason = BufferErrorReason::InvalidValue {
+    message: "unknown flag bits",
+};
+
+impl<F, B> Decoder<F, B> for Reject
+where
+    F: Flags,
+    F::Bits: TypeDecoder<B>,
+    B: SplittableBuffer,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<F, B> {
+        let (bits, buffer) = buffer.decode()?;
+        match F::from_bits(bits) {
+            Some(flags) => Ok((flags, buffer)),
+            None => Err(BufferError::new(buffer, UNKNOWN_BITS)),
+        }
+    }
+}
+
+impl<F, B> Encoder<F, B> for Reject
+where
+    F: Flags,
+    F::Bits: TypeEncoder<B>,
+    B: EncoderBuffer,
+{
+    #[inline(always)]
+    fn encode_into(self, value: F, buffer: B) -> Result<(), B> {
+        if F::from_bits(value.bits()).is_none() {
+            return Err(BufferError::new(buffer, UNKNOWN_BITS));
+        }
+        let (_, buffer) = buffer.encode(value.bits())?;
+        Ok(((), buffer))
+    }
+}
+
+impl<F, B> Decoder<F, B> for Truncate
+where
+    F: Flags,
+    F::Bits: TypeDecoder<B>,
+    B: SplittableBuffer,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<F, B> {
+        let (bits, buffer) = buffer.decode()?;
+        Ok((F::from_bits_truncate(bits), buffer))
+    }
+}
+
+impl<F, B> Encoder<F, B> for Truncate
+where
+    F: Flags,
+    F::Bits: TypeEncoder<B>,
+    B: EncoderBuffer,
+{
+    #[inline(always)]
+    fn encode_into(self, value: F, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(F::from_bits_truncate(value.bits()).bits())?;
+        Ok(((), buffer))
+    }
+}
+
+impl<F, B> Decoder<F, B> for Retain
+where
+    F: Flags,
+    F::Bits: TypeDecoder<B>,
+    B: SplittableBuffer,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<F, B> {
+        let (bits, buffer) = buffer.decode()?;
+        Ok((F::from_bits_retain(bits), buffer))
+    }
+}
+
+impl<F, B> Encoder<F, B> for Retain
+where
+    F: Flags,
+    F::Bits: TypeEncoder<B>,
+    B: EncoderBuffer,
+{
+    #[inline(always)]
+    fn encode_into(self, value: F, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(value.bits())?;
+        Ok(((), buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    bitflags::bitflags! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        struct Test: u16 {
+            const A = 0b0001;
+            const B = 0b0010;
+        }
+    }
+
+    #[test]
+    fn reject_test() {
+        let buffer = &[0, 0b0011][..];
+        let (value, _buffer) = buffer.decode_with(Reject).unwrap();
+        let value: Test = value;
+        assert_eq!(value, Test::A | Test::B);
+
+        let buffer = &[0, 0b0111][..];
+        let res: Result<Test, _> = buffer.decode_with(Reject);
+        assert!(res.is_err());
+
+        let mut out = [0u8; 2];
+        let res = (&mut out[..]).encode_with(Test::from_bits_retain(0b0100), Reject);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn truncate_test() {
+        let buffer = &[1, 0b0111][..];
+        let (value, _buffer) = buffer.decode_with(Truncate).unwrap();
+        let value: Test = value;
+        assert_eq!(value, Test::A | Test::B);
+
+        let mut out = [0u8; 2];
+        (&mut out[..])
+            .encode_with(Test::from_bits_retain(0b0101), Truncate)
+            .unwrap();
+        assert_eq!(out, [0, 0b0001]);
+    }
+
+    #[test]
+    fn retain_test() {
+        let buffer = &[1, 0b0111][..];
+        let (value, _buffer) = buffer.decode_with(Retain).unwrap();
+        let value: Test = value;
+        assert_eq!(value.bits(), 0x0107);
+
+        let mut out = [0u8; 2];
+        (&mut out[..]).encode_with(value, Retain).unwrap();
+        assert_eq!(out, [1, 0b0111]);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    buffer::{Result, SplittableBuffer},
+    decode::Decoder,
+    encode::{Encoder, EncoderBuffer},
+};
+use core::fmt;
+
+/// The maximum number of path segments recorded in an `ErrorContext`
+pub const MAX_ERROR_PATH: usize = 8;
+
+/// Additional information about where a `BufferError` occurred
+///
+/// The offset and path are only recorded when the `error-context` feature is
+/// enabled. Otherwise this type is zero-sized and all of the recording methods
+/// are no-ops.
+#[cfg(feature = "error-context")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ErrorContext {
+    offset: Option<usize>,
+    // segments are stored innermost first
+    path: [&'static str; MAX_ERROR_PATH],
+    path_len: usize,
+    truncated: bool,
+}
+
+#[cfg(feature = "error-context")]
+impl ErrorContext {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            offset: None,
+            path: [""; MAX_ERROR_PATH],
+            path_len: 0,
+            truncated: false,
+        }
+    }
+
+    #[inline(always)]
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Returns the path segments, outermost first
+    #[inline(always)]
+    pub fn path(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.path[..self.path_len].iter().rev().copied()
+    }
+
+    #[inline(always)]
+    pub fn or_offset(&mut self, offset: usize) {
+        if self.offset.is_none() {
+            self.offset = Some(offset);
+        }
+    }
+
+    #[inline(always)]
+    pub fn push_path(&mut self, name: &'static str) {
+        if self.path_len < MAX_ERROR_PATH {
+            self.path[self.path_len] = name;
+            self.path_len += 1;
+        } else {
+            self.truncated = true;
+        }
+    }
+}
+
+#[cfg(not(feature = "error-context"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ErrorContext;
+
+#[cfg(not(feature = "error-context"))]
+impl ErrorContext {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    pub fn offset(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline(always)]
+    pub fn path(&self) -> impl Iterator<Item = &'static str> + '_ {
+        core::iter::empty()
+    }
+
+    #[inline(always)]
+    pub fn or_offset(&mut self, offset: usize) {
+        let _ = offset;
+    }
+
+    #[inline(always)]
+    pub fn push_path(&mut self, name: &'static str) {
+        let _ = name;
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut path = self.path().peekable();
+        let has_path = path.peek().is_some();
+
+        if has_path {
+            #[cfg(feature = "error-context")]
+            {
+                if self.truncated {
+                    write!(f, "...")?;
+                }
+            }
+
+            for (idx, name) in path.enumerate() {
+                if idx > 0 {
+                    write!(f, ".")?;
+                }
+                write!(f, "{}", name)?;
+            }
+        }
+
+        if let Some(offset) = self.offset() {
+            if has_path {
+                write!(f, " ")?;
+            }
+            write!(f, "at offset {}", offset)?;
+        }
+
+        if has_path || self.offset().is_some() {
+            write!(f, ": ")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Records `name` in the error path of any failure from the wrapped codec
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Named<C> {
+    pub name: &'static str,
+    pub codec: C,
+}
+
+impl<C> Named<C> {
+    #[inline(always)]
+    pub const fn new(name: &'static str, codec: C) -> Self {
+        Self { name, codec }
+    }
+}
+
+impl<T, B: SplittableBuffer, D: Decoder<T, B>> Decoder<T, B> for Named<D> {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<T, B> {
+        let name = self.name;
+        self.codec
+            .decode_from(buffer)
+            .map_err(|err| err.in_path(name))
+    }
+}
+
+impl<T, B: EncoderBuffer, E: Encoder<T, B>> Encoder<T, B> for Named<E> {
+    #[inline(always)]
+    fn encode_into(self, value: T, buffer: B) -> Result<(), B> {
+        let name = self.name;
+        self.codec
+            .encode_into(value, buffer)
+            .map_err(|err| err.in_path(name))
+    }
+}
+
+#[cfg(all(test, feature = "error-context"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        let mut context = ErrorContext::default();
+        assert_eq!(format!("{}", context), "");
+
+        context.or_offset(12);
+        context.or_offset(4);
+        assert_eq!(format!("{}", context), "at offset 12: ");
+
+        context.push_path("len");
+        context.push_path("header");
+        assert_eq!(format!("{}", context), "header.len at offset 12: ");
+    }
+
+    #[test]
+    fn truncated_test() {
+        let mut context = ErrorContext::default();
+        for _ in 0..=MAX_ERROR_PATH {
+            context.push_path("a");
+        }
+        assert_eq!(context.path().count(), MAX_ERROR_PATH);
+        assert!(format!("{}", context).starts_with("...a.a"));
+    }
+
+    #[test]
+    fn decode_path_test() {
+        use crate::{buffer::Positioned, decode::DecoderBuffer, endian::Big};
+
+        let buffer = Positioned::new(&[1, 2, 3][..]);
+        let (_, buffer) = buffer.decode_named::<u16>("kind").unwrap();
+        let err = buffer
+            .decode_with(Named::new("header", Named::new("len", Big)))
+            .map(|(value, _): (u16, _)| value)
+            .unwrap_err();
+        assert_eq!(err.context.offset(), Some(2));
+        assert_eq!(
+            format!("{}", err),
+            "header.len at offset 2: unexpected EOF: actual = 1, expected = 2\n"
+        );
+    }
+}
@@ -1,20 +1,67 @@
 use crate::{
     buffer::{
-        BorrowedBuffer, BorrowedMutBuffer, BufferError, FiniteBuffer, FiniteMutBuffer, Result,
-        SplittableBuffer,
+        BorrowedBuffer, BorrowedMutBuffer, FiniteBuffer, FiniteMutBuffer, Result, SplittableBuffer,
     },
     encode::EncoderBuffer,
+    limit::Limits,
+};
+use core::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
 };
 
 macro_rules! impl_lookahead {
     ($name:ident, [$($derive:ident),*], $a:lifetime, $ty:ty) => {
-        #[derive($($derive,)* Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
-        pub struct $name<$a>($ty);
+        /// A borrowed view of a buffer's bytes that keeps the buffer's decode limits
+        #[derive($($derive,)* Debug)]
+        pub struct $name<$a> {
+            buffer: $ty,
+            limits: Option<&$a Limits>,
+        }
 
         impl<$a> $name<$a> {
             #[inline(always)]
             pub fn new(buffer: $ty) -> Self {
-                Self(buffer)
+                Self {
+                    buffer,
+                    limits: None,
+                }
+            }
+
+            /// Creates a buffer that enforces `limits`, if any
+            #[inline(always)]
+            pub fn with_limits(buffer: $ty, limits: Option<&$a Limits>) -> Self {
+                Self { buffer, limits }
+            }
+        }
+
+        impl<$a> PartialEq for $name<$a> {
+            #[inline(always)]
+            fn eq(&self, other: &Self) -> bool {
+                self.buffer == other.buffer
+            }
+        }
+
+        impl<$a> Eq for $name<$a> {}
+
+        impl<$a> PartialOrd for $name<$a> {
+            #[inline(always)]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<$a> Ord for $name<$a> {
+            #[inline(always)]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.buffer.cmp(&other.buffer)
+            }
+        }
+
+        impl<$a> Hash for $name<$a> {
+            #[inline(always)]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.buffer.hash(state)
             }
         }
 
@@ -23,21 +70,30 @@ macro_rules! impl_lookahead {
 
             #[inline(always)]
             fn checked_split(self, offset: usize) -> Result<Self::Slice, Self> {
-                let (a, b) = self.0.checked_split(offset).map_err(|err| err.map_buffer($name))?;
-                Ok(($name(a), $name(b)))
+                let limits = self.limits;
+                let (a, b) = self
+                    .buffer
+                    .checked_split(offset)
+                    .map_err(|err| err.map_buffer(|buffer| Self::with_limits(buffer, limits)))?;
+                Ok((Self::with_limits(a, limits), Self::with_limits(b, limits)))
+            }
+
+            #[inline(always)]
+            fn limits(&self) -> Option<&Limits> {
+                self.limits
             }
         }
 
         impl<$a> FiniteBuffer for $name<$a> {
             #[inline(always)]
             fn as_less_safe_slice(&self) -> &[u8] {
-                &self.0
+                &self.buffer
             }
         }
 
         impl<$a> BorrowedBuffer<$a> for $name<$a> {
             fn into_less_safe_slice(self) -> &$a [u8] {
-                self.0
+                self.buffer
             }
         }
     };
@@ -49,13 +105,18 @@ impl_lookahead!(LookaheadMutBuffer, [], 'a, &'a mut [u8]);
 impl<'a> FiniteMutBuffer for LookaheadMutBuffer<'a> {
     #[inline(always)]
     fn as_less_safe_mut_slice(&mut self) -> &mut [u8] {
-        &mut self.0
+        self.buffer
+    }
+
+    #[inline(always)]
+    fn as_less_safe_mut_slice_with_limits(&mut self) -> (&mut [u8], Option<&Limits>) {
+        (self.buffer, self.limits)
     }
 }
 
 impl<'a> BorrowedMutBuffer<'a> for LookaheadMutBuffer<'a> {
     fn into_less_safe_mut_slice(self) -> &'a mut [u8] {
-        self.0
+        self.buffer
     }
 }
 
@@ -79,20 +140,17 @@ impl<'a> EncoderBuffer for LookaheadMutBuffer<'a> {
     where
         F: FnOnce(Self) -> Result<(), Self>,
     {
-        let res = self.0.checkpoint(|buffer| match f(Self(buffer)) {
-            Ok(((), buffer)) => Ok(((), buffer.0)),
-            Err(err) => Err(BufferError {
-                buffer: err.buffer.0,
-                reason: err.reason,
-            }),
-        });
+        let limits = self.limits;
+        let res = self
+            .buffer
+            .checkpoint(|buffer| match f(Self::with_limits(buffer, limits)) {
+                Ok(((), buffer)) => Ok(((), buffer.buffer)),
+                Err(err) => Err(err.map_buffer(|buffer| buffer.buffer)),
+            });
 
         match res {
-            Ok((len, buffer)) => Ok((len, Self(buffer))),
-            Err(err) => Err(BufferError {
-                buffer: Self(err.buffer),
-                reason: err.reason,
-            }),
+            Ok((len, buffer)) => Ok((len, Self::with_limits(buffer, limits))),
+            Err(err) => Err(err.map_buffer(|buffer| Self::with_limits(buffer, limits))),
         }
     }
 }
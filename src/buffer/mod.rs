@@ -1,40 +1,55 @@
-use crate::decode::{Decoder, DecoderBuffer, TypeDecoder};
+use crate::{
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    limit::Limits,
+};
 use core::fmt;
 
 macro_rules! map_buffer_error {
     ($expr:expr, $prev:expr) => {{
         let res = match $expr {
             Ok((value, _)) => Ok(value),
-            Err(err) => Err(err.reason),
+            Err(err) => Err(err.with_buffer(())),
         };
 
         match res {
             Ok(value) => (value, $prev),
-            Err(reason) => {
-                return Err($crate::buffer::BufferError {
-                    buffer: $prev,
-                    reason,
-                });
+            Err(err) => {
+                return Err(err.with_buffer($prev));
             }
         }
     }};
 }
 
+mod context;
 mod lookahead;
+mod positioned;
 
+pub use context::*;
 pub use lookahead::*;
+pub use positioned::*;
 
 #[derive(Clone, Copy, Debug)]
 pub struct BufferError<B> {
     pub buffer: B,
     pub reason: BufferErrorReason,
+    pub context: ErrorContext,
 }
 
 impl<B> BufferError<B> {
+    #[inline(always)]
+    pub fn new(buffer: B, reason: BufferErrorReason) -> Self {
+        Self {
+            buffer,
+            reason,
+            context: ErrorContext::new(),
+        }
+    }
+
     pub fn with_buffer<NewB>(self, buffer: NewB) -> BufferError<NewB> {
         BufferError {
             buffer,
             reason: self.reason,
+            context: self.context,
         }
     }
 
@@ -42,12 +57,28 @@ impl<B> BufferError<B> {
         BufferError {
             buffer: map(self.buffer),
             reason: self.reason,
+            context: self.context,
         }
     }
+
+    /// Records the offset of the failure, unless one has already been recorded
+    #[inline(always)]
+    pub fn or_offset(mut self, offset: usize) -> Self {
+        self.context.or_offset(offset);
+        self
+    }
+
+    /// Records that the failure happened while processing `name`
+    #[inline(always)]
+    pub fn in_path(mut self, name: &'static str) -> Self {
+        self.context.push_path(name);
+        self
+    }
 }
 
 impl<B> fmt::Display for BufferError<B> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.context.fmt(f)?;
         self.reason.fmt(f)
     }
 }
@@ -55,11 +86,25 @@ impl<B> fmt::Display for BufferError<B> {
 #[cfg(feature = "std")]
 impl<B: core::fmt::Debug> std::error::Error for BufferError<B> {}
 
+/// The reason a buffer operation failed
+///
+/// New reasons may be added as codecs grow, so matches outside this crate need a
+/// wildcard arm.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum BufferErrorReason {
     UnexpectedEof { actual: usize, expected: usize },
     UnexpectedBytes { len: usize },
     InvalidValue { message: &'static str },
+    UnexpectedValue { expected: u128, actual: u128 },
+    UnknownTag { tag: u128 },
+    MissingTag { tag: u64 },
+    DuplicateTag { tag: u64 },
+    LengthOverflow,
+    InvalidUtf8 { valid_up_to: usize },
+    NonCanonical,
+    LimitExceeded { limit: usize },
+    ChecksumMismatch { expected: u64, actual: u64 },
 }
 
 impl fmt::Display for BufferErrorReason {
@@ -72,6 +117,25 @@ impl fmt::Display for BufferErrorReason {
             ),
             Self::UnexpectedBytes { len } => writeln!(f, "unexpected bytes: remaining = {}", len),
             Self::InvalidValue { message } => writeln!(f, "invalid value: {}", message),
+            Self::UnexpectedValue { expected, actual } => writeln!(
+                f,
+                "unexpected value: actual = {:#x}, expected = {:#x}",
+                actual, expected
+            ),
+            Self::UnknownTag { tag } => writeln!(f, "unknown tag: {:#x}", tag),
+            Self::MissingTag { tag } => writeln!(f, "missing tag: {:#x}", tag),
+            Self::DuplicateTag { tag } => writeln!(f, "duplicate tag: {:#x}", tag),
+            Self::LengthOverflow => writeln!(f, "length overflow"),
+            Self::InvalidUtf8 { valid_up_to } => {
+                writeln!(f, "invalid utf-8: valid_up_to = {}", valid_up_to)
+            }
+            Self::NonCanonical => writeln!(f, "non-canonical encoding"),
+            Self::LimitExceeded { limit } => writeln!(f, "limit exceeded: limit = {}", limit),
+            Self::ChecksumMismatch { expected, actual } => writeln!(
+                f,
+                "checksum mismatch: actual = {:#x}, expected = {:#x}",
+                actual, expected
+            ),
         }
     }
 }
@@ -86,6 +150,12 @@ pub trait SplittableBuffer: Sized {
 
     fn checked_split(self, len: usize) -> Result<Self::Slice, Self>;
 
+    /// Returns the decode limits that apply to this buffer, if any
+    #[inline(always)]
+    fn limits(&self) -> Option<&Limits> {
+        None
+    }
+
     #[inline(always)]
     fn checked_split_with<T, F: FnOnce(LookaheadBuffer) -> Result<T, LookaheadBuffer>>(
         self,
@@ -93,7 +163,10 @@ pub trait SplittableBuffer: Sized {
         f: F,
     ) -> Result<T, Self> {
         let (a, b) = self.checked_split(len)?;
-        let res = f(LookaheadBuffer::new(a.as_less_safe_slice()));
+        let res = f(LookaheadBuffer::with_limits(
+            a.as_less_safe_slice(),
+            a.limits(),
+        ));
         let (v, b) = map_buffer_error!(res, b);
         Ok((v, b))
     }
@@ -146,8 +219,8 @@ pub trait FiniteBuffer: SplittableBuffer<Slice = Self> {
     }
 
     #[inline(always)]
-    fn lookahead(&self) -> LookaheadBuffer {
-        LookaheadBuffer::new(self.as_less_safe_slice())
+    fn lookahead(&self) -> LookaheadBuffer<'_> {
+        LookaheadBuffer::with_limits(self.as_less_safe_slice(), self.limits())
     }
 
     #[inline(always)]
@@ -182,10 +255,10 @@ pub trait FiniteBuffer: SplittableBuffer<Slice = Self> {
         if actual >= expected {
             Ok((actual, self))
         } else {
-            Err(BufferError {
-                reason: BufferErrorReason::UnexpectedEof { actual, expected },
-                buffer: self,
-            })
+            Err(BufferError::new(
+                self,
+                BufferErrorReason::UnexpectedEof { actual, expected },
+            ))
         }
     }
 
@@ -194,10 +267,11 @@ pub trait FiniteBuffer: SplittableBuffer<Slice = Self> {
         if self.is_empty() {
             Ok(((), self))
         } else {
-            Err(BufferError {
-                reason: BufferErrorReason::UnexpectedBytes { len: self.len() },
-                buffer: self,
-            })
+            let len = self.len();
+            Err(BufferError::new(
+                self,
+                BufferErrorReason::UnexpectedBytes { len },
+            ))
         }
     }
 }
@@ -205,9 +279,18 @@ pub trait FiniteBuffer: SplittableBuffer<Slice = Self> {
 pub trait FiniteMutBuffer: FiniteBuffer {
     fn as_less_safe_mut_slice(&mut self) -> &mut [u8];
 
+    /// Returns the mutable bytes along with the decode limits that apply to them
+    ///
+    /// Buffers that carry limits override this so `lookahead_mut` keeps them.
     #[inline(always)]
-    fn lookahead_mut(&mut self) -> LookaheadMutBuffer {
-        LookaheadMutBuffer::new(self.as_less_safe_mut_slice())
+    fn as_less_safe_mut_slice_with_limits(&mut self) -> (&mut [u8], Option<&Limits>) {
+        (self.as_less_safe_mut_slice(), None)
+    }
+
+    #[inline(always)]
+    fn lookahead_mut(&mut self) -> LookaheadMutBuffer<'_> {
+        let (buffer, limits) = self.as_less_safe_mut_slice_with_limits();
+        LookaheadMutBuffer::with_limits(buffer, limits)
     }
 }
 
@@ -219,6 +302,55 @@ pub trait BorrowedMutBuffer<'a>: FiniteMutBuffer {
     fn into_less_safe_mut_slice(self) -> &'a mut [u8];
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reason_display_test() {
+        for (reason, expected) in [
+            (
+                BufferErrorReason::UnknownTag { tag: 0x2a },
+                "unknown tag: 0x2a\n",
+            ),
+            (
+                BufferErrorReason::MissingTag { tag: 0x2a },
+                "missing tag: 0x2a\n",
+            ),
+            (
+                BufferErrorReason::DuplicateTag { tag: 0x2a },
+                "duplicate tag: 0x2a\n",
+            ),
+            (BufferErrorReason::LengthOverflow, "length overflow\n"),
+            (
+                BufferErrorReason::InvalidUtf8 { valid_up_to: 3 },
+                "invalid utf-8: valid_up_to = 3\n",
+            ),
+            (BufferErrorReason::NonCanonical, "non-canonical encoding\n"),
+            (
+                BufferErrorReason::LimitExceeded { limit: 16 },
+                "limit exceeded: limit = 16\n",
+            ),
+            (
+                BufferErrorReason::ChecksumMismatch {
+                    expected: 0xbeef,
+                    actual: 0xdead,
+                },
+                "checksum mismatch: actual = 0xdead, expected = 0xbeef\n",
+            ),
+            (
+                BufferErrorReason::UnexpectedValue {
+                    expected: 1,
+                    actual: 0xff,
+                },
+                "unexpected value: actual = 0xff, expected = 0x1\n",
+            ),
+        ] {
+            assert_eq!(format!("{}", reason), expected);
+        }
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use crate::{
@@ -0,0 +1,230 @@
+use crate::{
+    buffer::{
+        BorrowedBuffer, BorrowedMutBuffer, FiniteBuffer, FiniteMutBuffer, LookaheadBuffer,
+        LookaheadMutBuffer, Result, SplittableBuffer, SplittableMutBuffer,
+    },
+    encode::EncoderBuffer,
+    limit::Limits,
+};
+
+/// A buffer that knows its offset from the start of the message
+pub trait PositionedBuffer {
+    fn position(&self) -> usize;
+}
+
+/// Tracks the offset of a buffer from the start of the message
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct Positioned<B> {
+    buffer: B,
+    position: usize,
+}
+
+impl<B> Positioned<B> {
+    #[inline(always)]
+    pub fn new(buffer: B) -> Self {
+        Self::with_position(buffer, 0)
+    }
+
+    #[inline(always)]
+    pub fn with_position(buffer: B, position: usize) -> Self {
+        Self { buffer, position }
+    }
+
+    #[inline(always)]
+    pub fn get_ref(&self) -> &B {
+        &self.buffer
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+}
+
+impl<B> PositionedBuffer for Positioned<B> {
+    #[inline(always)]
+    fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl<B: SplittableBuffer> SplittableBuffer for Positioned<B> {
+    type Slice = Positioned<B::Slice>;
+
+    #[inline(always)]
+    fn checked_split(self, len: usize) -> Result<Self::Slice, Self> {
+        let position = self.position;
+        let (a, b) = self.buffer.checked_split(len).map_err(|err| {
+            err.map_buffer(|buffer| Self::with_position(buffer, position))
+                .or_offset(position)
+        })?;
+        let a = Positioned::with_position(a, position);
+        let b = Self::with_position(b, position + len);
+        Ok((a, b))
+    }
+
+    #[inline(always)]
+    fn limits(&self) -> Option<&Limits> {
+        self.buffer.limits()
+    }
+
+    #[inline(always)]
+    fn checked_split_with<T, F: FnOnce(LookaheadBuffer) -> Result<T, LookaheadBuffer>>(
+        self,
+        len: usize,
+        f: F,
+    ) -> Result<T, Self> {
+        let position = self.position;
+        match self.buffer.checked_split_with(len, f) {
+            Ok((value, buffer)) => Ok((value, Self::with_position(buffer, position + len))),
+            Err(err) => Err(err
+                .map_buffer(|buffer| Self::with_position(buffer, position))
+                .or_offset(position)),
+        }
+    }
+}
+
+impl<B> SplittableMutBuffer for Positioned<B>
+where
+    B: SplittableMutBuffer,
+    B::Slice: FiniteMutBuffer,
+{
+    type FrozenSlice = Positioned<B::FrozenSlice>;
+
+    #[inline(always)]
+    fn slice_mut_with<T, F: FnOnce(LookaheadMutBuffer) -> Result<T, LookaheadMutBuffer>>(
+        self,
+        len: usize,
+        f: F,
+    ) -> Result<T, Self> {
+        let position = self.position;
+        match self.buffer.slice_mut_with(len, f) {
+            Ok((value, buffer)) => Ok((value, Self::with_position(buffer, position + len))),
+            Err(err) => Err(err
+                .map_buffer(|buffer| Self::with_position(buffer, position))
+                .or_offset(position)),
+        }
+    }
+
+    #[inline(always)]
+    fn freeze(self) -> Self::FrozenSlice {
+        Positioned::with_position(self.buffer.freeze(), self.position)
+    }
+}
+
+impl<B: FiniteBuffer> FiniteBuffer for Positioned<B> {
+    #[inline(always)]
+    fn as_less_safe_slice(&self) -> &[u8] {
+        self.buffer.as_less_safe_slice()
+    }
+}
+
+impl<B: FiniteMutBuffer> FiniteMutBuffer for Positioned<B> {
+    #[inline(always)]
+    fn as_less_safe_mut_slice(&mut self) -> &mut [u8] {
+        self.buffer.as_less_safe_mut_slice()
+    }
+
+    #[inline(always)]
+    fn as_less_safe_mut_slice_with_limits(&mut self) -> (&mut [u8], Option<&Limits>) {
+        self.buffer.as_less_safe_mut_slice_with_limits()
+    }
+}
+
+impl<'a, B: BorrowedBuffer<'a>> BorrowedBuffer<'a> for Positioned<B> {
+    #[inline(always)]
+    fn into_less_safe_slice(self) -> &'a [u8] {
+        self.buffer.into_less_safe_slice()
+    }
+}
+
+impl<'a, B: BorrowedMutBuffer<'a>> BorrowedMutBuffer<'a> for Positioned<B> {
+    #[inline(always)]
+    fn into_less_safe_mut_slice(self) -> &'a mut [u8] {
+        self.buffer.into_less_safe_mut_slice()
+    }
+}
+
+impl<B: EncoderBuffer> EncoderBuffer for Positioned<B> {
+    #[inline(always)]
+    fn encoder_capacity(&self) -> usize {
+        self.buffer.encoder_capacity()
+    }
+
+    #[inline(always)]
+    fn encode_bytes<T: AsRef<[u8]>>(self, bytes: T) -> Result<usize, Self> {
+        let position = self.position;
+        match self.buffer.encode_bytes(bytes) {
+            Ok((len, buffer)) => Ok((len, Self::with_position(buffer, position + len))),
+            Err(err) => Err(err
+                .map_buffer(|buffer| Self::with_position(buffer, position))
+                .or_offset(position)),
+        }
+    }
+
+    #[inline(always)]
+    fn checkpoint<F>(self, f: F) -> Result<usize, Self>
+    where
+        F: FnOnce(Self) -> Result<(), Self>,
+    {
+        let position = self.position;
+        let res = self
+            .buffer
+            .checkpoint(|buffer| match f(Self::with_position(buffer, position)) {
+                Ok(((), buffer)) => Ok(((), buffer.buffer)),
+                Err(err) => Err(err.map_buffer(|buffer| buffer.buffer)),
+            });
+
+        match res {
+            Ok((len, buffer)) => Ok((len, Self::with_position(buffer, position + len))),
+            Err(err) => Err(err
+                .map_buffer(|buffer| Self::with_position(buffer, position))
+                .or_offset(position)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        align::Padded,
+        decode::DecoderBuffer,
+        len::{LenPrefix, LenPrefixed},
+    };
+
+    #[test]
+    fn split_position_test() {
+        let buffer = Positioned::with_position(&[0u8, 1, 2, 3, 4][..], 10);
+        let (a, b) = buffer.checked_split(2).unwrap();
+        assert_eq!(a.position(), 10);
+        assert_eq!(b.position(), 12);
+
+        let err = b.checked_split(4).unwrap_err();
+        assert_eq!(err.buffer.position(), 12);
+    }
+
+    #[test]
+    fn nested_position_test() {
+        let buffer = Positioned::new(&[9u8, 2, 7, 0][..]);
+        let (_, buffer) = buffer.decode::<u8>().unwrap();
+        let (value, buffer) = buffer.decode::<LenPrefixed<Padded<u8, 4>, u8>>().unwrap();
+        assert_eq!((value.value).0, 7);
+        assert_eq!(buffer.position(), 4);
+    }
+
+    #[test]
+    fn encode_len_prefix_test() {
+        let mut out = [0u8; 4];
+        let buffer = Positioned::new(&mut out[..]);
+        let (_, buffer) = buffer.encode(1u8).unwrap();
+        let (_, buffer) = buffer.encode_with(2u16, LenPrefix::new::<u8>()).unwrap();
+        assert_eq!(buffer.position(), 4);
+        assert_eq!(out, [1, 2, 0, 2]);
+
+        let buffer = Positioned::new(&out[..]);
+        let (_, buffer) = buffer.decode::<u8>().unwrap();
+        let (value, _) = buffer.decode::<LenPrefixed<u16, u8>>().unwrap();
+        assert_eq!(value.value, 2);
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
         FiniteBuffer, FiniteMutBuffer, LookaheadBuffer, LookaheadMutBuffer, Result,
         SplittableBuffer, SplittableMutBuffer,
     },
+    decode::DecoderBuffer,
     encode::EncoderBuffer,
 };
 use bytes::BufMut;
@@ -123,6 +124,8 @@ macro_rules! impl_codec {
         impl<B: FiniteBuffer> crate::decode::TypeDecoder<B> for $ty {
             #[inline(always)]
             fn decode_type(buffer: B) -> Result<$ty, B> {
+                let len = buffer.len();
+                let ((), buffer) = buffer.reserve_alloc(len)?;
                 let (slice, buffer) = buffer.consume();
                 let $slice = slice.as_less_safe_slice();
                 let value = $new;
@@ -168,7 +171,7 @@ mod tests {
                 out = BytesMut::with_capacity(len);
             },
             |buffer| {
-                buffer.resize(len.max(buffer.len()), 0);
+                buffer.resize(core::cmp::max(len, buffer.len()), 0);
                 &buffer[..]
             }
         );
@@ -183,7 +186,7 @@ mod tests {
                 out = &mut buffer;
             },
             |buffer| {
-                buffer.resize(len.max(buffer.len()), 0);
+                buffer.resize(core::cmp::max(len, buffer.len()), 0);
                 &buffer[..]
             }
         );
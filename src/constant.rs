@@ -0,0 +1,319 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+use core::{convert::TryFrom, marker::PhantomData};
+use num_traits::Zero;
+
+/// Verifies the next bytes in the buffer match the expected magic bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Magic<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Magic<N> {
+    #[inline(always)]
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+/// Packs the 16-byte window containing `index` for error reporting
+#[inline(always)]
+fn pack_window(bytes: &[u8], index: usize) -> u128 {
+    let start = index - index % 16;
+    let end = bytes.len().min(start + 16);
+    bytes[start..end]
+        .iter()
+        .fold(0, |acc, byte| (acc << 8) | *byte as u128)
+}
+
+impl<B: SplittableBuffer, const N: usize> Decoder<(), B> for Magic<N> {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<(), B> {
+        buffer.checked_split_with(N, |slice| {
+            let actual = slice.as_less_safe_slice();
+            match actual.iter().zip(self.0.iter()).position(|(a, e)| a != e) {
+                None => Ok(((), slice)),
+                Some(index) => Err(BufferError::new(
+                    slice,
+                    BufferErrorReason::UnexpectedValue {
+                        expected: pack_window(&self.0, index),
+                        actual: pack_window(actual, index),
+                    },
+                )),
+            }
+        })
+    }
+}
+
+impl<B: EncoderBuffer, const N: usize> Encoder<(), B> for Magic<N> {
+    #[inline(always)]
+    fn encode_into(self, _value: (), buffer: B) -> Result<(), B> {
+        self.encode_type(buffer)
+    }
+}
+
+impl<B: EncoderBuffer, const N: usize> TypeEncoder<B> for Magic<N> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_bytes(self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, const N: usize> TypeEncoder<B> for &Magic<N> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (*self).encode_type(buffer)
+    }
+}
+
+/// An integer that can be compared against and reported in `UnexpectedValue` errors
+pub trait ConstValue: Copy + PartialEq {
+    /// Returns the value's bits, zero-extended to 128 bits
+    fn to_bits(self) -> u128;
+
+    /// Converts `value` into this type, if it is in range
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! impl_const_value {
+    ($($ty:ident => $unsigned:ident),* $(,)?) => {
+        $(
+            impl ConstValue for $ty {
+                #[inline(always)]
+                fn to_bits(self) -> u128 {
+                    self as $unsigned as u128
+                }
+
+                #[inline(always)]
+                fn from_i128(value: i128) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_const_value!(
+    u8 => u8,
+    u16 => u16,
+    u32 => u32,
+    u64 => u64,
+    u128 => u128,
+    usize => usize,
+    i8 => u8,
+    i16 => u16,
+    i32 => u32,
+    i64 => u64,
+    i128 => u128,
+    isize => usize,
+);
+
+/// Verifies the next value in the buffer equals the expected constant
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Const<T>(pub T);
+
+impl<T, B> Decoder<(), B> for Const<T>
+where
+    T: TypeDecoder<B> + ConstValue,
+    B: SplittableBuffer,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<(), B> {
+        let (actual, buffer) = buffer.decode::<T>()?;
+        if actual == self.0 {
+            Ok(((), buffer))
+        } else {
+            Err(BufferError::new(
+                buffer,
+                BufferErrorReason::UnexpectedValue {
+                    expected: self.0.to_bits(),
+                    actual: actual.to_bits(),
+                },
+            ))
+        }
+    }
+}
+
+impl<T: TypeEncoder<B>, B: EncoderBuffer> Encoder<(), B> for Const<T> {
+    #[inline(always)]
+    fn encode_into(self, _value: (), buffer: B) -> Result<(), B> {
+        self.encode_type(buffer)
+    }
+}
+
+impl<T: TypeEncoder<B>, B: EncoderBuffer> TypeEncoder<B> for Const<T> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, T, B: EncoderBuffer> TypeEncoder<B> for &'a Const<T>
+where
+    &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(&self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+/// A reserved field that is always encoded as zero
+///
+/// When `STRICT` is `true`, decoding fails if the field is non-zero. Otherwise
+/// the field's contents are ignored.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reserved<T, const STRICT: bool = false>(PhantomData<T>);
+
+impl<T, const STRICT: bool> Reserved<T, STRICT> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, const STRICT: bool> Clone for Reserved<T, STRICT> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const STRICT: bool> Copy for Reserved<T, STRICT> {}
+
+impl<T, const STRICT: bool> Default for Reserved<T, STRICT> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, B> TypeDecoder<B> for Reserved<T, false>
+where
+    T: TypeDecoder<B>,
+    B: SplittableBuffer,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (_, buffer) = buffer.decode::<T>()?;
+        Ok((Self::new(), buffer))
+    }
+}
+
+impl<T, B> TypeDecoder<B> for Reserved<T, true>
+where
+    T: TypeDecoder<B> + Zero + ConstValue,
+    B: SplittableBuffer,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let ((), buffer) = buffer.decode_with(Const(T::zero()))?;
+        Ok((Self::new(), buffer))
+    }
+}
+
+impl<T, B, const STRICT: bool> TypeEncoder<B> for Reserved<T, STRICT>
+where
+    T: TypeEncoder<B> + Zero,
+    B: EncoderBuffer,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(T::zero())?;
+        Ok(((), buffer))
+    }
+}
+
+impl<T, B, const STRICT: bool> TypeEncoder<B> for &Reserved<T, STRICT>
+where
+    T: TypeEncoder<B> + Zero,
+    B: EncoderBuffer,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (*self).encode_type(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn magic_test() {
+        let magic = Magic(*b"\x89PNG");
+
+        let buffer = &b"\x89PNG\r\n"[..];
+        let ((), buffer) = buffer.decode_with(magic).unwrap();
+        assert_eq!(buffer, b"\r\n");
+
+        let buffer = &b"\x89PNx"[..];
+        let err = buffer.decode_with(magic).unwrap_err();
+        assert_eq!(
+            err.reason,
+            BufferErrorReason::UnexpectedValue {
+                expected: 0x8950_4e47,
+                actual: 0x8950_4e78,
+            }
+        );
+
+        let mut out = [0u8; 4];
+        (&mut out[..]).encode(magic).unwrap();
+        assert_eq!(&out, b"\x89PNG");
+    }
+
+    #[test]
+    fn const_test() {
+        let buffer = &[0, 5, 1][..];
+        let ((), buffer) = buffer.decode_with(Const(5u16)).unwrap();
+        assert_eq!(buffer, &[1]);
+
+        let err = buffer.decode_with(Const(2u8)).unwrap_err();
+        assert_eq!(
+            err.reason,
+            BufferErrorReason::UnexpectedValue {
+                expected: 2,
+                actual: 1
+            }
+        );
+
+        let mut out = [0u8; 2];
+        (&mut out[..]).encode(Const(5u16)).unwrap();
+        assert_eq!(out, [0, 5]);
+    }
+
+    #[test]
+    fn signed_const_test() {
+        let buffer = &[0xff, 0xfe, 0xff][..];
+        let ((), buffer) = buffer.decode_with(Const(-2i16)).unwrap();
+
+        let err = buffer.decode_with(Const(1i8)).unwrap_err();
+        assert_eq!(
+            err.reason,
+            BufferErrorReason::UnexpectedValue {
+                expected: 1,
+                actual: 0xff
+            }
+        );
+
+        assert!((&[0u8; 4][..]).decode::<Reserved<i32, true>>().is_ok());
+        assert!((&[0xffu8; 4][..]).decode::<Reserved<i32, true>>().is_err());
+    }
+
+    #[test]
+    fn reserved_test() {
+        let buffer = &[1, 2][..];
+        let (_, buffer) = buffer.decode::<Reserved<u8>>().unwrap();
+        assert!(buffer.decode::<Reserved<u8, true>>().is_err());
+
+        let buffer = &[0, 0][..];
+        let (_, buffer) = buffer.decode::<Reserved<u16, true>>().unwrap();
+        assert!(buffer.is_empty());
+
+        let mut out = [1u8; 2];
+        (&mut out[..]).encode(Reserved::<u16>::new()).unwrap();
+        assert_eq!(out, [0, 0]);
+    }
+}
@@ -0,0 +1,361 @@
+#[cfg(feature = "alloc")]
+use crate::decode::Decoder;
+use crate::{
+    buffer::{BufferError, BufferErrorReason, Result, SplittableBuffer},
+    decode::{DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::mem::size_of;
+use core::{
+    convert::{TryFrom, TryInto},
+    marker::PhantomData,
+};
+
+/// Implements the sequence codecs for a fixed-capacity vector type `$ty<T, N>`
+///
+/// `$push` must append an item, returning an error instead of growing when the
+/// vector is full. Nothing is reserved against the buffer's allocation limit,
+/// since the items are stored inline.
+#[cfg(any(feature = "arrayvec", feature = "heapless"))]
+macro_rules! impl_fixed_vec {
+    ($ty:ident, $push:ident) => {
+        /// Decodes items until the buffer is empty, failing if there are more than `N`
+        impl<B: FiniteBuffer, T: TypeDecoder<B>, const N: usize> TypeDecoder<B> for $ty<T, N> {
+            #[inline(always)]
+            fn decode_type(buffer: B) -> Result<Self, B> {
+                buffer.decode_nested(|mut buffer| {
+                    let mut values = $ty::new();
+
+                    while !buffer.is_empty() {
+                        if values.is_full() {
+                            return Err(BufferError::new(
+                                buffer,
+                                BufferErrorReason::LimitExceeded { limit: N },
+                            ));
+                        }
+
+                        let len = buffer.len();
+                        let (value, next) = buffer.decode()?;
+
+                        // make sure we're making progress to avoid looping forever
+                        if next.len() == len {
+                            return Err(BufferError::new(
+                                next,
+                                BufferErrorReason::InvalidValue {
+                                    message: "sequence item consumed no bytes",
+                                },
+                            ));
+                        }
+
+                        // capacity was checked above
+                        let _ = values.$push(value);
+                        buffer = next;
+                    }
+
+                    Ok((values, buffer))
+                })
+            }
+        }
+
+        impl<B: EncoderBuffer, T, const N: usize> TypeEncoder<B> for $ty<T, N>
+        where
+            for<'a> &'a T: TypeEncoder<B>,
+        {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                (&self[..]).encode_type(buffer)
+            }
+        }
+
+        impl<B: EncoderBuffer, T, const N: usize> TypeEncoder<B> for &$ty<T, N>
+        where
+            for<'a> &'a T: TypeEncoder<B>,
+        {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                (&self[..]).encode_type(buffer)
+            }
+        }
+
+        impl<C, T, B, const N: usize> Decoder<$ty<T, N>, B> for CountPrefix<C>
+        where
+            B: SplittableBuffer,
+            C: TypeDecoder<B> + TryInto<usize>,
+            T: TypeDecoder<B>,
+        {
+            #[inline(always)]
+            fn decode_from(self, buffer: B) -> Result<$ty<T, N>, B> {
+                let (count, buffer) = Self::decode_count(buffer, N)?;
+
+                buffer.decode_nested(|mut buffer| {
+                    let mut values = $ty::new();
+                    for _ in 0..count {
+                        let (value, next) = buffer.decode()?;
+                        // capacity was checked by `decode_count`
+                        let _ = values.$push(value);
+                        buffer = next;
+                    }
+
+                    Ok((values, buffer))
+                })
+            }
+        }
+
+        impl<'a, C, T, B, const N: usize> Encoder<&'a $ty<T, N>, B> for CountPrefix<C>
+        where
+            B: EncoderBuffer,
+            C: TypeEncoder<B> + TryFrom<usize>,
+            &'a T: TypeEncoder<B>,
+        {
+            #[inline(always)]
+            fn encode_into(self, values: &'a $ty<T, N>, buffer: B) -> Result<(), B> {
+                encode_items::<C, T, B>(values, buffer)
+            }
+        }
+
+        impl<C, T, B, const N: usize> Encoder<$ty<T, N>, B> for CountPrefix<C>
+        where
+            B: EncoderBuffer,
+            C: TypeEncoder<B> + TryFrom<usize>,
+            for<'a> &'a T: TypeEncoder<B>,
+        {
+            #[inline(always)]
+            fn encode_into(self, values: $ty<T, N>, buffer: B) -> Result<(), B> {
+                encode_items::<C, T, B>(&values, buffer)
+            }
+        }
+    };
+    (@tests $ty:ident) => {
+        #[test]
+        fn vec_test() {
+            let buffer = &[4u8, 0, 1, 0, 2, 9][..];
+            let (value, buffer): ($ty<u16, 2>, _) =
+                buffer.decode_with(LenPrefix::new::<u8>()).unwrap();
+            assert_eq!(&value[..], [1, 2]);
+            assert_eq!(buffer, &[9]);
+
+            let err = (&[0u8, 1, 0, 2, 0, 3][..])
+                .decode::<$ty<u16, 2>>()
+                .unwrap_err();
+            assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 2 });
+
+            let mut out = [0u8; 4];
+            (&mut out[..]).encode(&value).unwrap();
+            assert_eq!(out, [0, 1, 0, 2]);
+        }
+
+        #[test]
+        fn count_prefix_test() {
+            let buffer = &[2u8, 5, 6][..];
+            let (value, _): ($ty<u8, 2>, _) = buffer.decode_with(CountPrefix::new::<u8>()).unwrap();
+            assert_eq!(&value[..], [5, 6]);
+
+            let err = (&[3u8, 5, 6, 7][..])
+                .decode_with(CountPrefix::new::<u8>())
+                .map(|(value, _): ($ty<u8, 2>, _)| value)
+                .unwrap_err();
+            assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 2 });
+
+            // inline storage isn't charged against the allocation limit
+            let limits = Limits::new().with_max_alloc(0);
+            let buffer = Limited::new(&[2u8, 5, 6][..], &limits);
+            let (_, _): ($ty<u8, 2>, _) = buffer.decode_with(CountPrefix::new::<u8>()).unwrap();
+            assert_eq!(limits.allocated(), 0);
+
+            let mut out = [0u8; 3];
+            (&mut out[..])
+                .encode_with(&value, CountPrefix::new::<u8>())
+                .unwrap();
+            assert_eq!(out, [2, 5, 6]);
+        }
+    };
+}
+
+/// Limits the up-front allocation for a sequence so a hostile count can't
+/// allocate more than this before any items are decoded
+#[cfg(feature = "alloc")]
+const MAX_PREALLOC_BYTES: usize = 4096;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CountPrefix<C>(PhantomData<C>);
+
+impl<C> Clone for CountPrefix<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for CountPrefix<C> {}
+
+impl<C> Default for CountPrefix<C> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl CountPrefix<()> {
+    #[allow(clippy::new_ret_no_self)]
+    pub const fn new<C>() -> CountPrefix<C> {
+        CountPrefix(PhantomData)
+    }
+}
+
+impl<C> CountPrefix<C> {
+    /// Decodes the item count, checking it against `capacity` and the buffer's
+    /// sequence length limit
+    #[cfg_attr(
+        not(any(feature = "alloc", feature = "arrayvec", feature = "heapless")),
+        allow(dead_code)
+    )]
+    #[inline(always)]
+    pub(crate) fn decode_count<B>(buffer: B, capacity: usize) -> Result<usize, B>
+    where
+        B: SplittableBuffer,
+        C: TypeDecoder<B> + TryInto<usize>,
+    {
+        let (count, buffer) = buffer.decode::<C>()?;
+        let count: usize = match count.try_into() {
+            Ok(count) => count,
+            Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+        if count > capacity {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::LimitExceeded { limit: capacity },
+            ));
+        }
+        let ((), buffer) = buffer.ensure_sequence_len(count)?;
+        Ok((count, buffer))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, T, B> Decoder<Vec<T>, B> for CountPrefix<C>
+where
+    B: SplittableBuffer,
+    C: TypeDecoder<B> + TryInto<usize>,
+    T: TypeDecoder<B>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Vec<T>, B> {
+        let (count, buffer) = Self::decode_count(buffer, usize::MAX)?;
+        let ((), buffer) = buffer.reserve_alloc(count.saturating_mul(size_of::<T>()))?;
+
+        buffer.decode_nested(|mut buffer| {
+            let capacity = count.min(MAX_PREALLOC_BYTES / size_of::<T>().max(1));
+            let mut values = Vec::with_capacity(capacity);
+
+            for _ in 0..count {
+                let (value, next) = buffer.decode()?;
+                values.push(value);
+                buffer = next;
+            }
+
+            Ok((values, buffer))
+        })
+    }
+}
+
+#[inline(always)]
+pub(crate) fn encode_items<'a, C, T, B>(values: &'a [T], buffer: B) -> Result<(), B>
+where
+    B: EncoderBuffer,
+    C: TypeEncoder<B> + TryFrom<usize>,
+    &'a T: TypeEncoder<B>,
+{
+    let count = match C::try_from(values.len()) {
+        Ok(count) => count,
+        Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+    };
+
+    let (_, mut buffer) = buffer.encode(count)?;
+    for value in values {
+        let (_, next) = buffer.encode(value)?;
+        buffer = next;
+    }
+
+    Ok(((), buffer))
+}
+
+impl<'a, C, T, B> Encoder<&'a [T], B> for CountPrefix<C>
+where
+    B: EncoderBuffer,
+    C: TypeEncoder<B> + TryFrom<usize>,
+    &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, values: &'a [T], buffer: B) -> Result<(), B> {
+        encode_items::<C, T, B>(values, buffer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, C, T, B> Encoder<&'a Vec<T>, B> for CountPrefix<C>
+where
+    B: EncoderBuffer,
+    C: TypeEncoder<B> + TryFrom<usize>,
+    &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, values: &'a Vec<T>, buffer: B) -> Result<(), B> {
+        encode_items::<C, T, B>(values, buffer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, T, B> Encoder<Vec<T>, B> for CountPrefix<C>
+where
+    B: EncoderBuffer,
+    C: TypeEncoder<B> + TryFrom<usize>,
+    for<'a> &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, values: Vec<T>, buffer: B) -> Result<(), B> {
+        encode_items::<C, T, B>(&values, buffer)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::limit::{Limited, Limits};
+
+    #[test]
+    fn round_trip_test() {
+        let buffer = &[2, 0, 1, 0, 2, 9][..];
+        let (value, buffer) = buffer.decode_with(CountPrefix::new::<u8>()).unwrap();
+        let value: Vec<u16> = value;
+        assert_eq!(value, [1, 2]);
+        assert_eq!(buffer, &[9]);
+
+        let mut out = [0u8; 5];
+        (&mut out[..])
+            .encode_with(&value, CountPrefix::new::<u8>())
+            .unwrap();
+        assert_eq!(out, [2, 0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn encode_overflow_test() {
+        let value = [0u8; 256];
+        let mut out = [0u8; 512];
+        let err = (&mut out[..])
+            .encode_with(&value[..], CountPrefix::new::<u8>())
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LengthOverflow);
+    }
+
+    #[test]
+    fn limit_test() {
+        let limits = Limits::new().with_max_alloc(16);
+        let buffer = Limited::new(&[0xff, 0xff, 0xff, 0xff][..], &limits);
+        let err = buffer
+            .decode_with(CountPrefix::new::<u32>())
+            .map(|(value, _): (Vec<u8>, _)| value)
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 16 });
+    }
+}
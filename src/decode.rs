@@ -1,4 +1,7 @@
-use crate::buffer::{FiniteBuffer, Result, SplittableBuffer};
+use crate::{
+    buffer::{BufferError, FiniteBuffer, Result, SplittableBuffer},
+    skip::SkipAll,
+};
 
 pub trait Decoder<T, B: SplittableBuffer>: Sized {
     fn decode_from(self, buffer: B) -> Result<T, B>;
@@ -18,17 +21,81 @@ pub trait DecoderBuffer: SplittableBuffer {
     fn decode_with<T, D: Decoder<T, Self>>(self, decoder: D) -> Result<T, Self> {
         decoder.decode_from(self)
     }
+
+    /// Decodes `T`, recording `name` in the error path on failure
+    #[inline(always)]
+    fn decode_named<T: TypeDecoder<Self>>(self, name: &'static str) -> Result<T, Self> {
+        T::decode_type(self).map_err(|err| err.in_path(name))
+    }
+
+    /// Reserves `len` bytes of allocation against the buffer's limits
+    #[inline(always)]
+    fn reserve_alloc(self, len: usize) -> Result<(), Self> {
+        match self
+            .limits()
+            .map_or(Ok(()), |limits| limits.reserve_alloc(len))
+        {
+            Ok(()) => Ok(((), self)),
+            Err(reason) => Err(BufferError::new(self, reason)),
+        }
+    }
+
+    /// Ensures a sequence of `len` items is within the buffer's limits
+    #[inline(always)]
+    fn ensure_sequence_len(self, len: usize) -> Result<(), Self> {
+        match self
+            .limits()
+            .map_or(Ok(()), |limits| limits.ensure_sequence_len(len))
+        {
+            Ok(()) => Ok(((), self)),
+            Err(reason) => Err(BufferError::new(self, reason)),
+        }
+    }
+
+    /// Calls `f` one nesting level deeper, as tracked by the buffer's limits
+    #[inline(always)]
+    fn decode_nested<T, F>(self, f: F) -> Result<T, Self>
+    where
+        F: FnOnce(Self) -> Result<T, Self>,
+    {
+        if let Err(reason) = self.limits().map_or(Ok(()), |limits| limits.enter()) {
+            return Err(BufferError::new(self, reason));
+        }
+
+        let res = f(self);
+
+        let buffer = match &res {
+            Ok((_, buffer)) => buffer,
+            Err(err) => &err.buffer,
+        };
+        if let Some(limits) = buffer.limits() {
+            limits.exit();
+        }
+
+        res
+    }
 }
 
 impl<B: SplittableBuffer> DecoderBuffer for B {}
 
+/// Skips all of the remaining bytes
+#[deprecated(note = "use `skip::SkipAll` instead")]
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Skip(pub usize);
 
+#[allow(deprecated)]
 impl<B: FiniteBuffer> TypeDecoder<B> for Skip {
+    #[inline(always)]
     fn decode_type(buffer: B) -> Result<Self, B> {
-        let (slice, buffer) = buffer.consume();
-        let len = slice.len();
+        let (SkipAll(len), buffer) = buffer.decode::<SkipAll>()?;
         Ok((Self(len), buffer))
     }
 }
+
+#[allow(deprecated)]
+impl From<Skip> for SkipAll {
+    #[inline(always)]
+    fn from(skip: Skip) -> Self {
+        Self(skip.0)
+    }
+}
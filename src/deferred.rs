@@ -0,0 +1,144 @@
+use crate::{
+    buffer::{
+        BufferError, BufferErrorReason, FiniteMutBuffer, Result, SplittableBuffer,
+        SplittableMutBuffer,
+    },
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, LenEstimator, TypeEncoder},
+    take::Exact,
+};
+use core::{
+    convert::{TryFrom, TryInto},
+    marker::PhantomData,
+};
+use num_traits::Zero;
+
+/// A length field that is separated from the payload it describes
+///
+/// After decoding the length, other fields can be decoded before the payload is
+/// decoded by passing the `DeferredLen` to `decode_with`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DeferredLen<L> {
+    pub len: L,
+}
+
+impl<L, B> TypeDecoder<B> for DeferredLen<L>
+where
+    L: TypeDecoder<B>,
+    B: SplittableBuffer,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (len, buffer) = buffer.decode()?;
+        Ok((Self { len }, buffer))
+    }
+}
+
+impl<L, T, B> Decoder<T, B> for DeferredLen<L>
+where
+    L: TryInto<usize>,
+    B: SplittableBuffer,
+    T: TypeDecoder<B::Slice>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<T, B> {
+        let len = match self.len.try_into() {
+            Ok(len) => len,
+            Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+        buffer.decode_with(Exact(len))
+    }
+}
+
+impl DeferredLen<()> {
+    /// Reserves space for a length field to be filled in once the payload is encoded
+    ///
+    /// The slot is sized by the encoding of a zero `L`, so `L` must encode to a
+    /// fixed number of bytes.
+    #[inline(always)]
+    pub fn reserve<L, E>(buffer: E) -> Result<LenSlot<L, E::Slice>, E>
+    where
+        L: TypeEncoder<LenEstimator> + Zero,
+        E: SplittableMutBuffer,
+        E::Slice: FiniteMutBuffer,
+    {
+        let len = match LenEstimator::encoding_len(L::zero(), usize::MAX) {
+            Ok(len) => len,
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
+        };
+        let (slot, buffer) = buffer.checked_split(len)?;
+        let slot = LenSlot {
+            slot,
+            len: PhantomData,
+        };
+        Ok((slot, buffer))
+    }
+}
+
+/// A reserved length field that must be filled in with `fill`
+#[must_use = "the reserved length must be filled"]
+#[derive(Debug)]
+pub struct LenSlot<L, S> {
+    slot: S,
+    len: PhantomData<L>,
+}
+
+impl<L, S> LenSlot<L, S>
+where
+    L: TypeEncoder<S> + TryFrom<usize>,
+    S: EncoderBuffer + FiniteMutBuffer,
+{
+    /// Writes the final length into the reserved slot
+    #[inline(always)]
+    pub fn fill(self, len: usize) -> core::result::Result<(), BufferError<S>> {
+        let len = match L::try_from(len) {
+            Ok(len) => len,
+            Err(_) => {
+                return Err(BufferError::new(
+                    self.slot,
+                    BufferErrorReason::LengthOverflow,
+                ))
+            }
+        };
+        let (_, slot) = self.slot.encode(len)?;
+        let ((), _) = slot.ensure_empty()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_deferred_test() {
+        let buffer = &[0, 2, 9, 0, 1, 7][..];
+        let (len, buffer) = buffer.decode::<DeferredLen<u16>>().unwrap();
+        let (kind, buffer) = buffer.decode::<u8>().unwrap();
+        let (payload, buffer) = buffer.decode_with(len).unwrap();
+        let payload: u16 = payload;
+        assert_eq!(kind, 9);
+        assert_eq!(payload, 1);
+        assert_eq!(buffer, &[7]);
+    }
+
+    #[test]
+    fn encode_deferred_test() {
+        let mut out = [0u8; 6];
+        let buffer = &mut out[..];
+        let (slot, buffer) = DeferredLen::reserve::<u16, _>(buffer).unwrap();
+        let (_, buffer) = buffer.encode(9u8).unwrap();
+        let (len, buffer) = buffer.encode((1u8, 2u8)).unwrap();
+        slot.fill(len).unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(out, [0, 2, 9, 1, 2, 0]);
+    }
+
+    #[test]
+    fn encode_deferred_overflow_test() {
+        let mut out = [0u8; 1];
+        let (slot, _) = DeferredLen::reserve::<u8, _>(&mut out[..]).unwrap();
+        let err = slot.fill(256).unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LengthOverflow);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     buffer::{
-        BufferError, BufferErrorReason, FiniteBuffer, FiniteMutBuffer, Result, SplittableBuffer,
+        BufferErrorReason, FiniteBuffer, FiniteMutBuffer, Result, SplittableBuffer,
         SplittableMutBuffer,
     },
     encode::{EncoderBuffer, TypeEncoder},
@@ -59,10 +59,7 @@ impl EncoderBuffer for LenEstimator {
                 let consumed_len = prev.len() - next.len();
                 Ok((consumed_len, next))
             }
-            Err(err) => Err(BufferError {
-                reason: err.reason,
-                buffer: prev,
-            }),
+            Err(err) => Err(err.with_buffer(prev)),
         }
     }
 }
@@ -161,10 +161,10 @@ pub trait EncoderBuffer: Sized {
         if actual >= expected {
             Ok((actual, self))
         } else {
-            Err(BufferError {
-                reason: BufferErrorReason::UnexpectedEof { actual, expected },
-                buffer: self,
-            })
+            Err(BufferError::new(
+                self,
+                BufferErrorReason::UnexpectedEof { actual, expected },
+            ))
         }
     }
 
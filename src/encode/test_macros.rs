@@ -72,6 +72,8 @@ macro_rules! __encoder_buffer_test {
             use crate::encode::EncoderBuffer;
 
             const $len: usize = $size;
+            // the init statements may borrow locals they declare, so assign afterwards
+            #[allow(clippy::needless_late_init)]
             let $buffer;
             $($init)*
 
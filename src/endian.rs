@@ -18,9 +18,9 @@ macro_rules! impl_endian {
             }
         }
 
-        impl Into<Endian> for $name {
+        impl From<$name> for Endian {
             #[inline(always)]
-            fn into(self) -> Endian {
+            fn from(_: $name) -> Self {
                 Endian::$name
             }
         }
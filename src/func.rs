@@ -0,0 +1,143 @@
+use crate::{
+    buffer::{Result, SplittableBuffer},
+    decode::Decoder,
+    encode::{Encoder, EncoderBuffer},
+};
+
+/// A decoder backed by a closure
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DecoderFn<F>(pub F);
+
+/// Wraps `f` so it can be passed anywhere a `Decoder` is expected
+#[inline(always)]
+pub fn decoder_fn<T, B, F>(f: F) -> DecoderFn<F>
+where
+    B: SplittableBuffer,
+    F: FnOnce(B) -> Result<T, B>,
+{
+    DecoderFn(f)
+}
+
+impl<T, B, F> Decoder<T, B> for DecoderFn<F>
+where
+    B: SplittableBuffer,
+    F: FnOnce(B) -> Result<T, B>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<T, B> {
+        (self.0)(buffer)
+    }
+}
+
+/// An encoder backed by a closure
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EncoderFn<F>(pub F);
+
+/// Wraps `f` so it can be passed anywhere an `Encoder` is expected
+#[inline(always)]
+pub fn encoder_fn<T, B, F>(f: F) -> EncoderFn<F>
+where
+    B: EncoderBuffer,
+    F: FnOnce(T, B) -> Result<(), B>,
+{
+    EncoderFn(f)
+}
+
+impl<T, B, F> Encoder<T, B> for EncoderFn<F>
+where
+    B: EncoderBuffer,
+    F: FnOnce(T, B) -> Result<(), B>,
+{
+    #[inline(always)]
+    fn encode_into(self, value: T, buffer: B) -> Result<(), B> {
+        (self.0)(value, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        buffer::{FiniteBuffer, LookaheadBuffer},
+        decode::DecoderBuffer,
+        len::LenPrefix,
+        tlv::{RawTlv, TlvWith, TLV},
+    };
+
+    #[test]
+    fn decode_test() {
+        let buffer = &[1u8, 2, 3][..];
+        let (value, buffer) = buffer
+            .decode_with(decoder_fn(|buffer: &[u8]| {
+                let (a, buffer) = buffer.decode::<u8>()?;
+                let (b, buffer) = buffer.decode::<u8>()?;
+                Ok((a + b, buffer))
+            }))
+            .unwrap();
+        assert_eq!(value, 3);
+        assert_eq!(buffer, &[3]);
+
+        let (value, _) = buffer
+            .peek_with(decoder_fn(|buffer: LookaheadBuffer| buffer.decode::<u8>()))
+            .unwrap();
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn len_prefix_test() {
+        let decoder = LenPrefix::new::<u8>().with(decoder_fn(|buffer: &[u8]| {
+            let (a, buffer) = buffer.decode::<u8>()?;
+            let (b, buffer) = buffer.decode::<u8>()?;
+            Ok((a + b, buffer))
+        }));
+        let (value, buffer) = (&[2u8, 7, 8, 9][..]).decode_with(decoder).unwrap();
+        assert_eq!(value, 15);
+        assert_eq!(buffer, &[9]);
+
+        let mut out = [0u8; 4];
+        let encoder = LenPrefix::new::<u8>().with(encoder_fn(|value: u8, buffer: &mut [u8]| {
+            let (_, buffer) = buffer.encode(value)?;
+            let (_, buffer) = buffer.encode(value)?;
+            Ok(((), buffer))
+        }));
+        let (_, buffer) = (&mut out[..]).encode_with(7u8, encoder).unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(out, [2, 7, 7, 0]);
+    }
+
+    #[test]
+    fn tlv_test() {
+        let (entry, _) = (&[1u8, 2, 0, 5][..])
+            .decode::<RawTlv<u8, u8, &[u8]>>()
+            .unwrap();
+        let (value, _) = entry
+            .decode_value_with(decoder_fn(|buffer: &[u8]| buffer.decode::<u16>()))
+            .unwrap();
+        assert_eq!(value, 5);
+
+        let decoder = decoder_fn(|buffer: &[u8]| buffer.decode::<u16>());
+        let (tlv, _): (TLV<u8, u8, u16>, _) =
+            (&[1u8, 2, 0, 5][..]).decode_with(TlvWith(decoder)).unwrap();
+        assert_eq!(tlv.value, 5);
+
+        let mut out = [0u8; 4];
+        let encoder = encoder_fn(|value: &u16, buffer: &mut [u8]| {
+            let (_, buffer) = buffer.encode(*value)?;
+            Ok(((), buffer))
+        });
+        (&mut out[..]).encode_with(&tlv, TlvWith(encoder)).unwrap();
+        assert_eq!(out, [1, 2, 0, 5]);
+    }
+
+    #[test]
+    fn encode_test() {
+        let mut out = [0u8; 3];
+        let encoder = encoder_fn(|value: u16, buffer: &mut [u8]| {
+            let (_, buffer) = buffer.encode(value)?;
+            let (_, buffer) = buffer.encode(0xffu8)?;
+            Ok(((), buffer))
+        });
+        (&mut out[..]).encode_with(0x0102, encoder).unwrap();
+        assert_eq!(out, [1, 2, 0xff]);
+    }
+}
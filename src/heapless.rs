@@ -0,0 +1,79 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
+    count::{encode_items, CountPrefix},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+use core::convert::{TryFrom, TryInto};
+use heapless::{String, Vec};
+
+impl_fixed_vec!(Vec, push);
+
+/// Decodes the remaining bytes as UTF-8, failing if there are more than `N`
+impl<B: FiniteBuffer, const N: usize> TypeDecoder<B> for String<N> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        if buffer.len() > N {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::LimitExceeded { limit: N },
+            ));
+        }
+
+        let mut value = String::new();
+        match core::str::from_utf8(buffer.as_less_safe_slice()) {
+            Ok(s) => {
+                // capacity was checked above
+                let _ = value.push_str(s);
+            }
+            Err(err) => {
+                let valid_up_to = err.valid_up_to();
+                return Err(BufferError::new(
+                    buffer,
+                    BufferErrorReason::InvalidUtf8 { valid_up_to },
+                ));
+            }
+        }
+
+        let (_, buffer) = buffer.consume();
+        Ok((value, buffer))
+    }
+}
+
+impl<B: EncoderBuffer, const N: usize> TypeEncoder<B> for String<N> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.as_str().encode_type(buffer)
+    }
+}
+
+impl<B: EncoderBuffer, const N: usize> TypeEncoder<B> for &String<N> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.as_str().encode_type(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        len::LenPrefix,
+        limit::{Limited, Limits},
+    };
+
+    impl_fixed_vec!(@tests Vec);
+
+    #[test]
+    fn string_test() {
+        let (value, _) = (&b"abc"[..]).decode::<String<4>>().unwrap();
+        assert_eq!(value, "abc");
+
+        let err = (&b"abcde"[..]).decode::<String<4>>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 4 });
+
+        let mut out = [0u8; 3];
+        (&mut out[..]).encode(&value).unwrap();
+        assert_eq!(&out, b"abc");
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     buffer::{
-        BufferError, FiniteBuffer, FiniteMutBuffer, Result, SplittableBuffer, SplittableMutBuffer,
+        BufferError, BufferErrorReason, FiniteBuffer, FiniteMutBuffer, Result, SplittableBuffer,
+        SplittableMutBuffer,
     },
     decode::{Decoder, DecoderBuffer, TypeDecoder},
     encode::{Encoder, EncoderBuffer, LenEstimator, TypeEncoder},
@@ -26,26 +27,145 @@ where
     #[inline(always)]
     fn decode_type(buffer: B) -> Result<Self, B> {
         let (len, buffer) = buffer.decode::<L>()?;
-        // If it doesn't fit then we most likely won't be able to read it anyway
-        let slice_len = len.try_into().unwrap_or(usize::MAX);
+        let slice_len = match len.try_into() {
+            Ok(slice_len) => slice_len,
+            Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
         let (slice, buffer) = buffer.checked_split(slice_len)?;
-        let (value, buffer) = map_buffer_error!(slice.consumed_decode(), buffer);
+        let (value, buffer) =
+            map_buffer_error!(slice.decode_nested(|slice| slice.consumed_decode()), buffer);
         Ok((Self { len, value }, buffer))
     }
 }
 
+/// Maps between the value of a length prefix and the number of bytes it describes
+pub trait LenMapping {
+    /// Returns the byte length described by `prefix`
+    fn to_len(prefix: usize, prefix_len: usize) -> core::result::Result<usize, BufferErrorReason>;
+
+    /// Returns the prefix describing `len` bytes
+    fn to_prefix(len: usize, prefix_len: usize) -> core::result::Result<usize, BufferErrorReason>;
+}
+
+/// The prefix is the number of bytes following it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Exclusive;
+
+impl LenMapping for Exclusive {
+    #[inline(always)]
+    fn to_len(prefix: usize, _prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        Ok(prefix)
+    }
+
+    #[inline(always)]
+    fn to_prefix(len: usize, _prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        Ok(len)
+    }
+}
+
+/// The prefix includes the bytes of the prefix itself
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Inclusive;
+
+impl LenMapping for Inclusive {
+    #[inline(always)]
+    fn to_len(prefix: usize, prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        prefix
+            .checked_sub(prefix_len)
+            .ok_or(BufferErrorReason::InvalidValue {
+                message: "length prefix is smaller than the prefix itself",
+            })
+    }
+
+    #[inline(always)]
+    fn to_prefix(len: usize, prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        len.checked_add(prefix_len)
+            .ok_or(BufferErrorReason::LengthOverflow)
+    }
+}
+
+/// The prefix is expressed in units of `SCALE` bytes
+///
+/// A `SCALE` of zero is rejected at compile time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Scaled<const SCALE: usize>;
+
+impl<const SCALE: usize> Scaled<SCALE> {
+    const NON_ZERO: () = assert!(SCALE != 0, "SCALE must be non-zero");
+}
+
+impl<const SCALE: usize> LenMapping for Scaled<SCALE> {
+    #[inline(always)]
+    fn to_len(prefix: usize, _prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        let () = Self::NON_ZERO;
+        prefix
+            .checked_mul(SCALE)
+            .ok_or(BufferErrorReason::LengthOverflow)
+    }
+
+    #[inline(always)]
+    fn to_prefix(len: usize, _prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        let () = Self::NON_ZERO;
+        if !len.is_multiple_of(SCALE) {
+            return Err(BufferErrorReason::InvalidValue {
+                message: "length is not a multiple of the prefix scale",
+            });
+        }
+        Ok(len / SCALE)
+    }
+}
+
+/// The prefix is stored as the byte length plus `OFFSET`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Offset<const OFFSET: isize>;
+
+impl<const OFFSET: isize> LenMapping for Offset<OFFSET> {
+    #[inline(always)]
+    fn to_len(prefix: usize, _prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        let len = if OFFSET < 0 {
+            prefix.checked_add(OFFSET.unsigned_abs())
+        } else {
+            prefix.checked_sub(OFFSET as usize)
+        };
+        len.ok_or(BufferErrorReason::LengthOverflow)
+    }
+
+    #[inline(always)]
+    fn to_prefix(len: usize, _prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        let prefix = if OFFSET < 0 {
+            len.checked_sub(OFFSET.unsigned_abs())
+        } else {
+            len.checked_add(OFFSET as usize)
+        };
+        prefix.ok_or(BufferErrorReason::LengthOverflow)
+    }
+}
+
+/// Applies `A` followed by `B` when mapping a prefix to a length
+impl<A: LenMapping, B: LenMapping> LenMapping for (A, B) {
+    #[inline(always)]
+    fn to_len(prefix: usize, prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        B::to_len(A::to_len(prefix, prefix_len)?, prefix_len)
+    }
+
+    #[inline(always)]
+    fn to_prefix(len: usize, prefix_len: usize) -> core::result::Result<usize, BufferErrorReason> {
+        A::to_prefix(B::to_prefix(len, prefix_len)?, prefix_len)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct LenPrefix<L>(PhantomData<L>);
+pub struct LenPrefix<L, M = Exclusive>(PhantomData<(L, M)>);
 
-impl<L> Clone for LenPrefix<L> {
+impl<L, M> Clone for LenPrefix<L, M> {
     fn clone(&self) -> Self {
-        Self(PhantomData)
+        *self
     }
 }
 
-impl<L> Copy for LenPrefix<L> {}
+impl<L, M> Copy for LenPrefix<L, M> {}
 
-impl<L> Default for LenPrefix<L> {
+impl<L, M> Default for LenPrefix<L, M> {
     fn default() -> Self {
         Self(PhantomData)
     }
@@ -58,19 +178,102 @@ impl LenPrefix<()> {
     }
 }
 
-impl<L, T, B: FiniteBuffer> Decoder<T, B> for LenPrefix<L>
+impl<L, M> LenPrefix<L, M> {
+    /// Includes the bytes of the prefix itself in the length
+    #[inline(always)]
+    pub const fn inclusive(self) -> LenPrefix<L, (M, Inclusive)> {
+        LenPrefix(PhantomData)
+    }
+
+    /// Expresses the length in units of `SCALE` bytes
+    #[inline(always)]
+    pub const fn scaled<const SCALE: usize>(self) -> LenPrefix<L, (M, Scaled<SCALE>)> {
+        LenPrefix(PhantomData)
+    }
+
+    /// Stores the length plus `OFFSET`
+    #[inline(always)]
+    pub const fn offset<const OFFSET: isize>(self) -> LenPrefix<L, (M, Offset<OFFSET>)> {
+        LenPrefix(PhantomData)
+    }
+}
+
+impl<L, M> LenPrefix<L, M> {
+    /// Decodes or encodes the prefixed bytes with `codec`
+    ///
+    /// Decoding requires all of the prefixed bytes to be consumed.
+    #[inline(always)]
+    pub fn with<D>(self, codec: D) -> LenPrefixWith<L, M, D> {
+        LenPrefixWith {
+            prefix: self,
+            codec,
+        }
+    }
+
+    #[inline(always)]
+    fn decode_slice<T, B, F>(buffer: B, f: F) -> Result<T, B>
+    where
+        B: FiniteBuffer,
+        L: TypeDecoder<B> + TryInto<usize> + Copy,
+        M: LenMapping,
+        F: FnOnce(B::Slice) -> Result<T, B::Slice>,
+    {
+        let initial_len = buffer.len();
+        let (prefix, buffer) = buffer.decode::<L>()?;
+        let prefix_len = initial_len - buffer.len();
+
+        let len = match prefix.try_into() {
+            Ok(prefix) => M::to_len(prefix, prefix_len),
+            Err(_) => Err(BufferErrorReason::LengthOverflow),
+        };
+        let len = match len {
+            Ok(len) => len,
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
+        };
+
+        let (slice, buffer) = buffer.checked_split(len)?;
+        let (value, buffer) = map_buffer_error!(slice.decode_nested(f), buffer);
+        Ok((value, buffer))
+    }
+}
+
+impl<L, M, T, B: FiniteBuffer> Decoder<T, B> for LenPrefix<L, M>
 where
     L: TypeDecoder<B> + TryInto<usize> + Copy,
+    M: LenMapping,
     T: TypeDecoder<B::Slice>,
 {
     #[inline(always)]
     fn decode_from(self, buffer: B) -> Result<T, B> {
-        let (value, buffer) = <LenPrefixed<T, L>>::decode_type(buffer)?;
-        Ok((value.value, buffer))
+        Self::decode_slice(buffer, |slice| slice.consumed_decode())
+    }
+}
+
+/// A length prefix whose bytes are decoded or encoded with a custom codec
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LenPrefixWith<L, M, D> {
+    prefix: LenPrefix<L, M>,
+    codec: D,
+}
+
+impl<L, M, D, T, B: FiniteBuffer> Decoder<T, B> for LenPrefixWith<L, M, D>
+where
+    L: TypeDecoder<B> + TryInto<usize> + Copy,
+    M: LenMapping,
+    D: Decoder<T, B::Slice>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<T, B> {
+        let decoder = self.codec;
+        LenPrefix::<L, M>::decode_slice(buffer, |slice| {
+            let (value, slice) = slice.decode_with(decoder)?;
+            let ((), slice) = slice.ensure_empty()?;
+            Ok((value, slice))
+        })
     }
 }
 
-impl<T, L, E> Encoder<T, E> for LenPrefix<L>
+impl<T, L, M, E> Encoder<T, E> for LenPrefix<L, M>
 where
     E: EncoderBuffer + SplittableBuffer + SplittableMutBuffer,
     <E as SplittableBuffer>::Slice: EncoderBuffer + FiniteMutBuffer,
@@ -80,6 +283,7 @@ where
         + TryFrom<usize>
         + Bounded
         + Copy,
+    M: LenMapping,
     T: TypeEncoder<<E as SplittableBuffer>::Slice>,
     for<'a> &'a T: TypeEncoder<LenEstimator>,
 {
@@ -93,28 +297,38 @@ where
         // compute the maximum bytes the prefix needs
         let prefix_len = match LenEstimator::encoding_len(max_value, capacity) {
             Ok(len) => len,
-            Err(reason) => return Err(BufferError { reason, buffer }),
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
         };
 
         // remove the prefix requirement
         let capacity = capacity - prefix_len;
 
         // bind the capacity to the maximum encodable prefix
-        let capacity = capacity.min(L::max_value().try_into().unwrap_or(usize::MAX));
+        let max_len = L::max_value()
+            .try_into()
+            .ok()
+            .and_then(|max_prefix| M::to_len(max_prefix, prefix_len).ok())
+            .unwrap_or(usize::MAX);
+        let capacity = capacity.min(max_len);
 
         // compute how many bytes the value needs
         let value_len = match LenEstimator::encoding_len(&value, capacity) {
             Ok(len) => len,
-            Err(reason) => return Err(BufferError { reason, buffer }),
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
         };
 
         // compute the actual prefix
-        let prefix: L = value_len.try_into().ok().expect("len should always fit");
+        let prefix = M::to_prefix(value_len, prefix_len)
+            .and_then(|prefix| L::try_from(prefix).map_err(|_| BufferErrorReason::LengthOverflow));
+        let prefix: L = match prefix {
+            Ok(prefix) => prefix,
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
+        };
 
         // compute how many bytes the actual prefix needs
         let prefix_len = match LenEstimator::encoding_len(prefix, capacity) {
             Ok(len) => len,
-            Err(reason) => return Err(BufferError { reason, buffer }),
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
         };
 
         // slice off the buffer to ensure the value encoder has the correct capacity
@@ -132,6 +346,56 @@ where
     }
 }
 
+/// Encodes the value with the custom encoder, then fills in the prefix
+///
+/// Since the value's length isn't known up front, the prefix is given the width
+/// of the largest prefix that fits in the buffer, which suits fixed-width `L`.
+impl<T, L, M, D, E> Encoder<T, E> for LenPrefixWith<L, M, D>
+where
+    E: EncoderBuffer + SplittableBuffer,
+    <E as SplittableBuffer>::Slice: EncoderBuffer + FiniteMutBuffer,
+    L: TypeEncoder<<E as SplittableBuffer>::Slice>
+        + TypeEncoder<LenEstimator>
+        + TryFrom<usize>
+        + Bounded
+        + Copy,
+    M: LenMapping,
+    D: Encoder<T, E>,
+{
+    #[inline(always)]
+    fn encode_into(self, value: T, buffer: E) -> Result<(), E> {
+        let capacity = buffer.encoder_capacity();
+
+        // compute the maximum prefix given the current buffer capacity
+        let max_value: L = capacity.try_into().unwrap_or_else(|_| L::max_value());
+
+        // reserve the bytes the maximum prefix needs
+        let prefix_len = match LenEstimator::encoding_len(max_value, capacity) {
+            Ok(len) => len,
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
+        };
+        let (slot, buffer) = buffer.checked_split(prefix_len)?;
+
+        let (value_len, buffer) = buffer.encode_with(value, self.codec)?;
+
+        // compute the actual prefix
+        let prefix = M::to_prefix(value_len, prefix_len)
+            .and_then(|prefix| L::try_from(prefix).map_err(|_| BufferErrorReason::LengthOverflow));
+        let prefix: L = match prefix {
+            Ok(prefix) => prefix,
+            Err(reason) => return Err(BufferError::new(buffer, reason)),
+        };
+
+        match slot
+            .encode(prefix)
+            .and_then(|(_, slot)| slot.ensure_empty())
+        {
+            Ok((_, _)) => Ok(((), buffer)),
+            Err(err) => Err(err.with_buffer(buffer)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,6 +408,13 @@ mod tests {
         assert_eq!(value, 1);
     }
 
+    #[test]
+    fn decode_len_prefix_overflow_test() {
+        let buffer = &[0xff, 0][..];
+        let err = buffer.decode::<LenPrefixed<u8, i8>>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LengthOverflow);
+    }
+
     #[test]
     fn encode_len_prefix_test() {
         let mut buffer = [0; 3];
@@ -173,6 +444,75 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn inclusive_len_prefix_test() {
+        let buffer = &[3, 0, 1][..];
+        let (value, _) = buffer
+            .decode_with(LenPrefix::new::<u8>().inclusive())
+            .unwrap();
+        let value: u16 = value;
+        assert_eq!(value, 1);
+
+        let buffer = &[0, 0, 1][..];
+        let res: Result<u16, _> = buffer.decode_with(LenPrefix::new::<u8>().inclusive());
+        assert!(res.is_err());
+
+        let mut buffer = [0; 3];
+        (&mut buffer[..])
+            .encode_with(1u16, LenPrefix::new::<u8>().inclusive())
+            .unwrap();
+        assert_eq!(buffer, [3, 0, 1]);
+    }
+
+    #[test]
+    fn scaled_len_prefix_test() {
+        let buffer = &[1, 0, 0, 0, 1][..];
+        let (value, _) = buffer
+            .decode_with(LenPrefix::new::<u8>().scaled::<4>())
+            .unwrap();
+        let value: u32 = value;
+        assert_eq!(value, 1);
+
+        let mut buffer = [0; 5];
+        (&mut buffer[..])
+            .encode_with(1u32, LenPrefix::new::<u8>().scaled::<4>())
+            .unwrap();
+        assert_eq!(buffer, [1, 0, 0, 0, 1]);
+
+        let err = (&mut buffer[..])
+            .encode_with(1u16, LenPrefix::new::<u8>().scaled::<4>())
+            .unwrap_err();
+        assert!(matches!(err.reason, BufferErrorReason::InvalidValue { .. }));
+    }
+
+    #[test]
+    fn scaled_inclusive_len_prefix_test() {
+        // IPv4 IHL-style: in 4-byte words, including the prefix
+        let buffer = &[1, 0, 0, 1, 9][..];
+        let (value, buffer) = buffer
+            .decode_with(LenPrefix::new::<u8>().scaled::<4>().inclusive())
+            .unwrap();
+        let value: (u8, u16) = value;
+        assert_eq!(value, (0, 1));
+        assert_eq!(buffer, &[9]);
+    }
+
+    #[test]
+    fn offset_len_prefix_test() {
+        let buffer = &[0, 0, 1][..];
+        let (value, _) = buffer
+            .decode_with(LenPrefix::new::<u8>().offset::<{ -2 }>())
+            .unwrap();
+        let value: u16 = value;
+        assert_eq!(value, 1);
+
+        let mut buffer = [0; 3];
+        (&mut buffer[..])
+            .encode_with(1u16, LenPrefix::new::<u8>().offset::<{ -2 }>())
+            .unwrap();
+        assert_eq!(buffer, [0, 0, 1]);
+    }
+
     #[test]
     fn decode_value_len_prefix_test() {
         let buffer = &[2, 0, 1][..];
@@ -1,3 +1,9 @@
+// recording error context intentionally trades error size for diagnostics
+#![cfg_attr(feature = "error-context", allow(clippy::result_large_err))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[macro_use]
 pub mod buffer;
 #[macro_use]
@@ -5,17 +11,45 @@ pub mod decode;
 #[macro_use]
 pub mod encode;
 
+pub mod align;
+pub mod alt;
+pub mod constant;
+#[macro_use]
+pub mod count;
+pub mod deferred;
 pub mod endian;
+pub mod func;
 pub mod len;
+pub mod limit;
+pub mod map;
+pub mod net;
+pub mod option;
 pub mod prim;
+pub mod skip;
 pub mod slice;
+pub mod string;
+pub mod take;
 pub mod tlv;
+pub mod union;
 
-#[cfg(feature = "std")]
-pub mod net;
+#[cfg(feature = "alloc")]
+pub mod ptr;
+#[cfg(feature = "alloc")]
+pub mod vec;
+
+#[cfg(feature = "arrayvec")]
+pub mod arrayvec;
+
+#[cfg(feature = "bitflags")]
+pub mod bitflags;
 
 #[cfg(feature = "bytes")]
 pub mod bytes;
 
+#[cfg(feature = "heapless")]
+pub mod heapless;
+
+#[cfg(feature = "zerocopy")]
+pub mod packet;
 #[cfg(feature = "zerocopy")]
 pub mod zerocopy;
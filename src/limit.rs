@@ -0,0 +1,269 @@
+use crate::buffer::{
+    BorrowedBuffer, BufferErrorReason, FiniteBuffer, FiniteMutBuffer, PositionedBuffer, Result,
+    SplittableBuffer,
+};
+use core::cell::Cell;
+
+/// Resource limits enforced while decoding untrusted input
+///
+/// A single `Limits` value is shared by every buffer split off from a
+/// `Limited` buffer, so allocations are accounted across the whole message.
+#[derive(Debug)]
+pub struct Limits {
+    max_alloc: usize,
+    max_sequence_len: usize,
+    max_depth: usize,
+    allocated: Cell<usize>,
+    depth: Cell<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Limits {
+    /// Creates a set of limits that allows everything
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            max_alloc: usize::MAX,
+            max_sequence_len: usize::MAX,
+            max_depth: usize::MAX,
+            allocated: Cell::new(0),
+            depth: Cell::new(0),
+        }
+    }
+
+    /// Sets the maximum number of bytes that decoders may allocate in total
+    #[inline(always)]
+    pub fn with_max_alloc(mut self, max_alloc: usize) -> Self {
+        self.max_alloc = max_alloc;
+        self
+    }
+
+    /// Sets the maximum number of items in a single decoded sequence
+    #[inline(always)]
+    pub fn with_max_sequence_len(mut self, max_sequence_len: usize) -> Self {
+        self.max_sequence_len = max_sequence_len;
+        self
+    }
+
+    /// Sets the maximum nesting depth of decoded values
+    #[inline(always)]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Returns the number of bytes reserved so far
+    #[inline(always)]
+    pub fn allocated(&self) -> usize {
+        self.allocated.get()
+    }
+
+    /// Returns the current nesting depth
+    #[inline(always)]
+    pub fn depth(&self) -> usize {
+        self.depth.get()
+    }
+
+    #[inline(always)]
+    pub fn reserve_alloc(&self, len: usize) -> core::result::Result<(), BufferErrorReason> {
+        let allocated = self.allocated.get().saturating_add(len);
+        if allocated > self.max_alloc {
+            return Err(BufferErrorReason::LimitExceeded {
+                limit: self.max_alloc,
+            });
+        }
+        self.allocated.set(allocated);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn ensure_sequence_len(&self, len: usize) -> core::result::Result<(), BufferErrorReason> {
+        if len > self.max_sequence_len {
+            return Err(BufferErrorReason::LimitExceeded {
+                limit: self.max_sequence_len,
+            });
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn enter(&self) -> core::result::Result<(), BufferErrorReason> {
+        let depth = self.depth.get();
+        if depth >= self.max_depth {
+            return Err(BufferErrorReason::LimitExceeded {
+                limit: self.max_depth,
+            });
+        }
+        self.depth.set(depth + 1);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn exit(&self) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+    }
+}
+
+/// Applies a set of `Limits` to a buffer and everything split off from it
+#[derive(Clone, Copy, Debug)]
+pub struct Limited<'a, B> {
+    buffer: B,
+    limits: &'a Limits,
+}
+
+impl<'a, B> Limited<'a, B> {
+    #[inline(always)]
+    pub fn new(buffer: B, limits: &'a Limits) -> Self {
+        Self { buffer, limits }
+    }
+
+    #[inline(always)]
+    pub fn get_ref(&self) -> &B {
+        &self.buffer
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+}
+
+impl<'a, B: SplittableBuffer> SplittableBuffer for Limited<'a, B> {
+    type Slice = Limited<'a, B::Slice>;
+
+    #[inline(always)]
+    fn checked_split(self, len: usize) -> Result<Self::Slice, Self> {
+        let limits = self.limits;
+        let (a, b) = self
+            .buffer
+            .checked_split(len)
+            .map_err(|err| err.map_buffer(|buffer| Self::new(buffer, limits)))?;
+        Ok((Limited::new(a, limits), Self::new(b, limits)))
+    }
+
+    #[inline(always)]
+    fn limits(&self) -> Option<&Limits> {
+        Some(self.limits)
+    }
+}
+
+impl<'a, B: FiniteBuffer> FiniteBuffer for Limited<'a, B> {
+    #[inline(always)]
+    fn as_less_safe_slice(&self) -> &[u8] {
+        self.buffer.as_less_safe_slice()
+    }
+}
+
+impl<'a, B: FiniteMutBuffer> FiniteMutBuffer for Limited<'a, B> {
+    #[inline(always)]
+    fn as_less_safe_mut_slice(&mut self) -> &mut [u8] {
+        self.buffer.as_less_safe_mut_slice()
+    }
+
+    #[inline(always)]
+    fn as_less_safe_mut_slice_with_limits(&mut self) -> (&mut [u8], Option<&Limits>) {
+        (self.buffer.as_less_safe_mut_slice(), Some(self.limits))
+    }
+}
+
+impl<'a, 'b, B: BorrowedBuffer<'b>> BorrowedBuffer<'b> for Limited<'a, B> {
+    #[inline(always)]
+    fn into_less_safe_slice(self) -> &'b [u8] {
+        self.buffer.into_less_safe_slice()
+    }
+}
+
+impl<'a, B: PositionedBuffer> PositionedBuffer for Limited<'a, B> {
+    #[inline(always)]
+    fn position(&self) -> usize {
+        self.buffer.position()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode::DecoderBuffer, len::LenPrefixed};
+
+    #[test]
+    fn depth_test() {
+        type Nested = LenPrefixed<LenPrefixed<LenPrefixed<u8, u8>, u8>, u8>;
+
+        let input = &[3, 2, 1, 7][..];
+
+        let limits = Limits::new().with_max_depth(3);
+        let (value, _) = Limited::new(input, &limits).decode::<Nested>().unwrap();
+        assert_eq!(value.value.value.value, 7);
+        assert_eq!(limits.depth(), 0);
+
+        let limits = Limits::new().with_max_depth(2);
+        let err = Limited::new(input, &limits).decode::<Nested>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 2 });
+        assert_eq!(limits.depth(), 0);
+    }
+
+    #[test]
+    fn lookahead_test() {
+        type Nested = LenPrefixed<LenPrefixed<u8, u8>, u8>;
+
+        let limits = Limits::new().with_max_depth(1);
+        let buffer = Limited::new(&[2, 1, 7][..], &limits);
+
+        let err = buffer.peek::<Nested>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 1 });
+
+        let err = buffer
+            .checked_split_with(3, |slice| {
+                assert!(slice.limits().is_some());
+                slice.decode::<Nested>()
+            })
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 1 });
+        assert_eq!(limits.depth(), 0);
+
+        let mut input = [2, 1, 7];
+        let mut buffer = Limited::new(&mut input[..], &limits);
+        let lookahead = buffer.lookahead_mut();
+        assert!(lookahead.limits().is_some());
+        let err = lookahead.decode::<Nested>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 1 });
+        assert_eq!(limits.depth(), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sequence_depth_test() {
+        use crate::count::CountPrefix;
+        use alloc::vec::Vec;
+
+        let input = &[1, 2, 3][..];
+        let decoder = CountPrefix::new::<u8>();
+
+        let limits = Limits::new().with_max_depth(2);
+        let (value, _) = Limited::new(input, &limits)
+            .decode_with::<Vec<Vec<u8>>, _>(decoder)
+            .unwrap();
+        assert_eq!(value, [[2, 3]]);
+
+        let limits = Limits::new().with_max_depth(1);
+        let err = Limited::new(input, &limits)
+            .decode_with::<Vec<Vec<u8>>, _>(decoder)
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 1 });
+        assert_eq!(limits.depth(), 0);
+    }
+
+    #[test]
+    fn reserve_alloc_test() {
+        let limits = Limits::new().with_max_alloc(4);
+        let buffer = Limited::new(&[][..], &limits);
+        let ((), buffer) = buffer.reserve_alloc(3).unwrap();
+        assert!(buffer.reserve_alloc(2).is_err());
+        assert_eq!(limits.allocated(), 3);
+    }
+}
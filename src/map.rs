@@ -0,0 +1,207 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, Result, SplittableBuffer},
+    decode::{Decoder, DecoderBuffer},
+    encode::{Encoder, EncoderBuffer},
+};
+use core::marker::PhantomData;
+
+/// Adapter methods available on every decoder
+pub trait DecoderExt<T, B: SplittableBuffer>: Decoder<T, B> {
+    /// Converts the decoded value with `f`
+    #[inline(always)]
+    fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Map<Self, F, T, B> {
+        Map {
+            decoder: self,
+            f,
+            value: PhantomData,
+        }
+    }
+
+    /// Converts the decoded value with a fallible `f`, failing with `InvalidValue` on error
+    #[inline(always)]
+    fn try_map<U, E, F: FnOnce(T) -> core::result::Result<U, E>>(
+        self,
+        f: F,
+    ) -> TryMap<Self, F, T, B> {
+        TryMap {
+            decoder: self,
+            f,
+            value: PhantomData,
+        }
+    }
+
+    /// Decodes the rest of the value with the decoder returned by `f`
+    #[inline(always)]
+    fn and_then<D, F: FnOnce(T) -> D>(self, f: F) -> AndThen<Self, F, T, B> {
+        AndThen {
+            decoder: self,
+            f,
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T, B: SplittableBuffer, D: Decoder<T, B>> DecoderExt<T, B> for D {}
+
+/// Adapter methods available on every encoder
+pub trait EncoderExt<T, B: EncoderBuffer>: Encoder<T, B> {
+    /// Converts values with `f` before encoding them
+    #[inline(always)]
+    fn contramap<U, F: FnOnce(U) -> T>(self, f: F) -> Contramap<Self, F, T, B> {
+        Contramap {
+            encoder: self,
+            f,
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T, B: EncoderBuffer, E: Encoder<T, B>> EncoderExt<T, B> for E {}
+
+/// A decoder whose value is converted by a function
+#[derive(Clone, Copy, Debug)]
+pub struct Map<D, F, T, B> {
+    decoder: D,
+    f: F,
+    value: PhantomData<fn(T, B)>,
+}
+
+impl<T, U, B, D, F> Decoder<U, B> for Map<D, F, T, B>
+where
+    B: SplittableBuffer,
+    D: Decoder<T, B>,
+    F: FnOnce(T) -> U,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<U, B> {
+        let (value, buffer) = buffer.decode_with(self.decoder)?;
+        Ok(((self.f)(value), buffer))
+    }
+}
+
+/// A decoder whose value is converted by a fallible function
+#[derive(Clone, Copy, Debug)]
+pub struct TryMap<D, F, T, B> {
+    decoder: D,
+    f: F,
+    value: PhantomData<fn(T, B)>,
+}
+
+impl<T, U, E, B, D, F> Decoder<U, B> for TryMap<D, F, T, B>
+where
+    B: SplittableBuffer,
+    D: Decoder<T, B>,
+    F: FnOnce(T) -> core::result::Result<U, E>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<U, B> {
+        let (value, buffer) = buffer.decode_with(self.decoder)?;
+        match (self.f)(value) {
+            Ok(value) => Ok((value, buffer)),
+            Err(_) => Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "conversion failed",
+                },
+            )),
+        }
+    }
+}
+
+/// A decoder whose value selects the decoder for the rest of the value
+#[derive(Clone, Copy, Debug)]
+pub struct AndThen<D, F, T, B> {
+    decoder: D,
+    f: F,
+    value: PhantomData<fn(T, B)>,
+}
+
+impl<T, U, B, D, N, F> Decoder<U, B> for AndThen<D, F, T, B>
+where
+    B: SplittableBuffer,
+    D: Decoder<T, B>,
+    N: Decoder<U, B>,
+    F: FnOnce(T) -> N,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<U, B> {
+        let (value, buffer) = buffer.decode_with(self.decoder)?;
+        buffer.decode_with((self.f)(value))
+    }
+}
+
+/// An encoder that converts values by a function before encoding them
+#[derive(Clone, Copy, Debug)]
+pub struct Contramap<E, F, U, B> {
+    encoder: E,
+    f: F,
+    value: PhantomData<fn(U, B)>,
+}
+
+impl<T, U, B, E, F> Encoder<T, B> for Contramap<E, F, U, B>
+where
+    B: EncoderBuffer,
+    E: Encoder<U, B>,
+    F: FnOnce(T) -> U,
+{
+    #[inline(always)]
+    fn encode_into(self, value: T, buffer: B) -> Result<(), B> {
+        self.encoder.encode_into((self.f)(value), buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endian::Big, len::LenPrefix};
+    use core::{convert::TryFrom, num::NonZeroU16, time::Duration};
+
+    #[test]
+    fn map_test() {
+        let buffer = &[0u8, 0, 0, 0, 0, 0, 1, 0][..];
+        let (value, _) = buffer.decode_with(Big.map(Duration::from_millis)).unwrap();
+        assert_eq!(value, Duration::from_millis(256));
+
+        // the adapters don't shadow `Iterator::map` on non-decoders
+        let doubled: u8 = [1u8, 2].iter().map(|v| v * 2).sum();
+        assert_eq!(doubled, 6);
+    }
+
+    #[test]
+    fn try_map_test() {
+        let decoder = || Big.try_map(|v: u16| NonZeroU16::try_from(v));
+
+        let (value, _) = (&[0u8, 3][..]).decode_with(decoder()).unwrap();
+        assert_eq!(value.get(), 3);
+
+        let err = (&[0u8, 0][..]).decode_with(decoder()).unwrap_err();
+        assert_eq!(
+            err.reason,
+            BufferErrorReason::InvalidValue {
+                message: "conversion failed"
+            }
+        );
+    }
+
+    #[test]
+    fn and_then_test() {
+        let buffer = &[0u8, 1, 2, 0, 7, 9][..];
+        let decoder = Big.and_then(|kind: u16| {
+            assert_eq!(kind, 1);
+            LenPrefix::new::<u8>()
+        });
+        let (value, buffer): (u16, _) = buffer.decode_with(decoder).unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(buffer, &[9]);
+    }
+
+    #[test]
+    fn contramap_test() {
+        let mut out = [0u8; 2];
+        let encoder = Big.contramap(|d: Duration| d.as_millis() as u16);
+        (&mut out[..])
+            .encode_with(Duration::from_millis(258), encoder)
+            .unwrap();
+        assert_eq!(out, [1, 2]);
+    }
+}
@@ -1,9 +1,14 @@
 use crate::{
-    buffer::{Result, SplittableBuffer},
-    decode::{DecoderBuffer, TypeDecoder},
-    encode::{EncoderBuffer, TypeEncoder},
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
+    constant::{ConstValue, Reserved},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+    union::{Either, Tagged, Tags},
+};
+use core::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
 };
-use std::net::{Ipv4Addr, Ipv6Addr};
 
 macro_rules! addr {
     ($ty:ident, $prim:ident) => {
@@ -43,14 +48,429 @@ macro_rules! addr {
 addr!(Ipv4Addr, u32);
 addr!(Ipv6Addr, u128);
 
-/// asserts that the endian is correct
-#[test]
-fn ipv4_round_trip_test() {
-    let localhost = &Ipv4Addr::LOCALHOST.octets()[..];
-    let (actual, _) = localhost.decode::<Ipv4Addr>().unwrap();
-    assert_eq!(actual, Ipv4Addr::LOCALHOST);
+macro_rules! by_ref {
+    ($ty:ty) => {
+        impl<B: EncoderBuffer> TypeEncoder<B> for &$ty {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                (*self).encode_type(buffer)
+            }
+        }
+
+        impl<B: EncoderBuffer> TypeEncoder<B> for &mut $ty {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                (*self).encode_type(buffer)
+            }
+        }
+    };
+}
+
+/// Address followed by the port
+impl<B: SplittableBuffer> TypeDecoder<B> for SocketAddrV4 {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (ip, buffer) = buffer.decode()?;
+        let (port, buffer) = buffer.decode()?;
+        Ok((Self::new(ip, port), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for SocketAddrV4 {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(*self.ip())?;
+        let (_, buffer) = buffer.encode(self.port())?;
+        Ok(((), buffer))
+    }
+}
+
+by_ref!(SocketAddrV4);
+
+/// Address followed by the port, with a zero flowinfo and scope id
+impl<B: SplittableBuffer> TypeDecoder<B> for SocketAddrV6 {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (ip, buffer) = buffer.decode()?;
+        let (port, buffer) = buffer.decode()?;
+        Ok((Self::new(ip, port, 0, 0), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for SocketAddrV6 {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(*self.ip())?;
+        let (_, buffer) = buffer.encode(self.port())?;
+        Ok(((), buffer))
+    }
+}
+
+by_ref!(SocketAddrV6);
+
+/// Encodes a `SocketAddrV6` including its flowinfo and scope id after the port
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlowScope;
+
+impl<B: SplittableBuffer> Decoder<SocketAddrV6, B> for FlowScope {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<SocketAddrV6, B> {
+        let (ip, buffer) = buffer.decode()?;
+        let (port, buffer) = buffer.decode()?;
+        let (flowinfo, buffer) = buffer.decode()?;
+        let (scope_id, buffer) = buffer.decode()?;
+        Ok((SocketAddrV6::new(ip, port, flowinfo, scope_id), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> Encoder<SocketAddrV6, B> for FlowScope {
+    #[inline(always)]
+    fn encode_into(self, value: SocketAddrV6, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(value)?;
+        let (_, buffer) = buffer.encode(value.flowinfo())?;
+        let (_, buffer) = buffer.encode(value.scope_id())?;
+        Ok(((), buffer))
+    }
+}
+
+/// Address families identified by the IP version number
+pub type IpVersion = Tags<u8, 4, 6>;
+
+/// Address families as identified by the SOCKS5 `ATYP` field
+pub type Socks5Family = Tags<u8, 1, 4>;
+
+/// Address families as identified by the STUN address attribute family field
+///
+/// This only encodes `IpAddr` values, since STUN places the port between the
+/// family and the address. Use `StunAddress` for a `SocketAddr`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StunFamily;
+
+impl<B: SplittableBuffer> Decoder<IpAddr, B> for StunFamily {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<IpAddr, B> {
+        buffer.decode_with(Tags::<u8, 1, 2>::new())
+    }
+}
+
+impl<B: EncoderBuffer> Encoder<IpAddr, B> for StunFamily {
+    #[inline(always)]
+    fn encode_into(self, value: IpAddr, buffer: B) -> Result<(), B> {
+        Tags::<u8, 1, 2>::new().encode_into(value, buffer)
+    }
+}
+
+/// Encodes a `SocketAddr` as the value of a STUN `MAPPED-ADDRESS` attribute
+///
+/// The layout is a reserved byte, the family, the port and then the address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StunAddress;
+
+impl<B: SplittableBuffer> Decoder<SocketAddr, B> for StunAddress {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<SocketAddr, B> {
+        let (_, buffer) = buffer.decode::<Reserved<u8>>()?;
+        let (family, buffer) = buffer.decode::<u8>()?;
+        let (port, buffer) = buffer.decode()?;
+        match family {
+            1 => {
+                let (ip, buffer) = buffer.decode()?;
+                Ok((SocketAddr::V4(SocketAddrV4::new(ip, port)), buffer))
+            }
+            2 => {
+                let (ip, buffer) = buffer.decode()?;
+                Ok((SocketAddr::V6(SocketAddrV6::new(ip, port, 0, 0)), buffer))
+            }
+            tag => Err(BufferError::new(
+                buffer,
+                BufferErrorReason::UnknownTag { tag: tag as u128 },
+            )),
+        }
+    }
+}
+
+impl<B: EncoderBuffer> Encoder<SocketAddr, B> for StunAddress {
+    #[inline(always)]
+    fn encode_into(self, value: SocketAddr, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(Reserved::<u8>::new())?;
+        let buffer = match value {
+            SocketAddr::V4(addr) => {
+                let (_, buffer) = buffer.encode(1u8)?;
+                let (_, buffer) = buffer.encode(addr.port())?;
+                let (_, buffer) = buffer.encode(*addr.ip())?;
+                buffer
+            }
+            SocketAddr::V6(addr) => {
+                let (_, buffer) = buffer.encode(2u8)?;
+                let (_, buffer) = buffer.encode(addr.port())?;
+                let (_, buffer) = buffer.encode(*addr.ip())?;
+                buffer
+            }
+        };
+        Ok(((), buffer))
+    }
+}
+
+macro_rules! family {
+    ($ty:ident, $v4:ident, $v6:ident) => {
+        impl<T, B, const V4: i128, const V6: i128> Decoder<$ty, B> for Tags<T, V4, V6>
+        where
+            B: SplittableBuffer,
+            T: TypeDecoder<B> + ConstValue,
+        {
+            #[inline(always)]
+            fn decode_from(self, buffer: B) -> Result<$ty, B> {
+                let (value, buffer) = buffer.decode::<Tagged<Self, $v4, $v6>>()?;
+                let value = match value.into_inner() {
+                    Either::First(addr) => $ty::V4(addr),
+                    Either::Second(addr) => $ty::V6(addr),
+                };
+                Ok((value, buffer))
+            }
+        }
+
+        impl<T, B, const V4: i128, const V6: i128> Encoder<$ty, B> for Tags<T, V4, V6>
+        where
+            B: EncoderBuffer,
+            T: TypeEncoder<B> + ConstValue,
+        {
+            #[inline(always)]
+            fn encode_into(self, value: $ty, buffer: B) -> Result<(), B> {
+                let value = match value {
+                    $ty::V4(addr) => Tagged::<Self, _, _>::first(addr),
+                    $ty::V6(addr) => Tagged::second(addr),
+                };
+                let (_, buffer) = buffer.encode(value)?;
+                Ok(((), buffer))
+            }
+        }
+
+        /// Preceded by the IP version number
+        impl<B: SplittableBuffer> TypeDecoder<B> for $ty {
+            #[inline(always)]
+            fn decode_type(buffer: B) -> Result<Self, B> {
+                buffer.decode_with(IpVersion::new())
+            }
+        }
+
+        impl<B: EncoderBuffer> TypeEncoder<B> for $ty {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                IpVersion::new().encode_into(self, buffer)
+            }
+        }
+
+        by_ref!($ty);
+    };
+}
+
+family!(IpAddr, Ipv4Addr, Ipv6Addr);
+family!(SocketAddr, SocketAddrV4, SocketAddrV6);
+
+macro_rules! hw_addr {
+    ($(#[$meta:meta])* $name:ident, $len:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub [u8; $len]);
+
+        impl $name {
+            pub const LEN: usize = $len;
+
+            #[inline(always)]
+            pub const fn octets(&self) -> [u8; $len] {
+                self.0
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            #[inline(always)]
+            fn from(octets: [u8; $len]) -> Self {
+                Self(octets)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            #[inline(always)]
+            fn from(addr: $name) -> Self {
+                addr.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                for (i, octet) in self.0.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(":")?;
+                    }
+                    write!(f, "{:02x}", octet)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl<B: SplittableBuffer> TypeDecoder<B> for $name {
+            #[inline(always)]
+            fn decode_type(buffer: B) -> Result<Self, B> {
+                let (slice, buffer) = buffer.checked_split($len)?;
+                let mut octets = [0u8; $len];
+                octets.copy_from_slice(slice.as_less_safe_slice());
+                Ok((Self(octets), buffer))
+            }
+        }
+
+        impl<B: EncoderBuffer> TypeEncoder<B> for $name {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                let (_, buffer) = buffer.encode_bytes(self.0)?;
+                Ok(((), buffer))
+            }
+        }
+
+        by_ref!($name);
+    };
+}
+
+hw_addr!(
+    /// A 48-bit IEEE 802 MAC address
+    MacAddr,
+    6
+);
+
+hw_addr!(
+    /// A 64-bit IEEE extended unique identifier
+    Eui64,
+    8
+);
+
+impl MacAddr {
+    pub const BROADCAST: Self = Self([0xff; 6]);
+
+    #[inline(always)]
+    pub const fn is_broadcast(&self) -> bool {
+        matches!(self.0, [0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+    }
+
+    #[inline(always)]
+    pub const fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Expands the address to a modified EUI-64, as used in IPv6 interface identifiers
+    #[inline(always)]
+    pub const fn to_modified_eui64(&self) -> Eui64 {
+        let [a, b, c, d, e, f] = self.0;
+        Eui64([a ^ 0x02, b, c, 0xff, 0xfe, d, e, f])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// asserts that the endian is correct
+    #[test]
+    fn ipv4_round_trip_test() {
+        let localhost = &Ipv4Addr::LOCALHOST.octets()[..];
+        let (actual, _) = localhost.decode::<Ipv4Addr>().unwrap();
+        assert_eq!(actual, Ipv4Addr::LOCALHOST);
+
+        let mut out = [0u8; 4];
+        (&mut out[..]).encode(Ipv4Addr::LOCALHOST).unwrap();
+        assert_eq!(out, localhost);
+    }
+
+    #[test]
+    fn socket_addr_test() {
+        let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 80);
+        let mut out = [0u8; 6];
+        (&mut out[..]).encode(addr).unwrap();
+        assert_eq!(out, [127, 0, 0, 1, 0, 80]);
+
+        let (actual, _) = (&out[..]).decode::<SocketAddrV4>().unwrap();
+        assert_eq!(actual, addr);
+
+        let addr = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 7, 9);
+        let mut out = [0u8; 26];
+        (&mut out[..]).encode_with(addr, FlowScope).unwrap();
+        let (actual, _) = (&out[..]).decode_with(FlowScope).unwrap();
+        assert_eq!(actual, addr);
+
+        let (actual, _) = (&out[..18]).decode::<SocketAddrV6>().unwrap();
+        assert_eq!(actual, SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0));
+    }
+
+    #[test]
+    fn family_test() {
+        let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let mut out = [0u8; 5];
+        (&mut out[..]).encode(addr).unwrap();
+        assert_eq!(out, [4, 127, 0, 0, 1]);
+
+        let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1080));
+        let mut out = [0u8; 7];
+        (&mut out[..])
+            .encode_with(addr, Socks5Family::new())
+            .unwrap();
+        assert_eq!(out, [1, 127, 0, 0, 1, 0x04, 0x38]);
+
+        let (actual, _) = (&out[..])
+            .decode_with::<SocketAddr, _>(Socks5Family::new())
+            .unwrap();
+        assert_eq!(actual, addr);
+
+        assert!((&[4u8, 0, 0, 0, 0][..])
+            .decode_with::<IpAddr, _>(Socks5Family::new())
+            .is_err());
+    }
+
+    #[test]
+    fn stun_address_test() {
+        let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 3478));
+        let mut out = [0xffu8; 8];
+        (&mut out[..]).encode_with(addr, StunAddress).unwrap();
+        assert_eq!(out, [0, 1, 0x0d, 0x96, 192, 0, 2, 1]);
+
+        let (actual, _) = (&out[..])
+            .decode_with::<SocketAddr, _>(StunAddress)
+            .unwrap();
+        assert_eq!(actual, addr);
+
+        let addr = SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 3478, 0, 0));
+        let mut out = [0u8; 20];
+        (&mut out[..]).encode_with(addr, StunAddress).unwrap();
+        assert_eq!(&out[..4], [0, 2, 0x0d, 0x96]);
+        let (actual, _) = (&out[..])
+            .decode_with::<SocketAddr, _>(StunAddress)
+            .unwrap();
+        assert_eq!(actual, addr);
+
+        let err = (&[0u8, 3, 0, 0][..])
+            .decode_with::<SocketAddr, _>(StunAddress)
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::UnknownTag { tag: 3 });
+
+        let (ip, _) = (&[1u8, 192, 0, 2, 1][..])
+            .decode_with::<IpAddr, _>(StunFamily)
+            .unwrap();
+        assert_eq!(ip, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+
+        let mut out = [0u8; 5];
+        (&mut out[..]).encode_with(ip, StunFamily).unwrap();
+        assert_eq!(out, [1, 192, 0, 2, 1]);
+    }
 
-    let mut out = [0u8; 4];
-    (&mut out[..]).encode(Ipv4Addr::LOCALHOST).unwrap();
-    assert_eq!(out, localhost);
+    #[test]
+    fn mac_addr_test() {
+        let (addr, _) = (&[0x00u8, 0x1b, 0x2c, 0x3d, 0x4e, 0x5f][..])
+            .decode::<MacAddr>()
+            .unwrap();
+        assert_eq!(format!("{}", addr), "00:1b:2c:3d:4e:5f");
+        assert_eq!(
+            addr.to_modified_eui64(),
+            Eui64([0x02, 0x1b, 0x2c, 0xff, 0xfe, 0x3d, 0x4e, 0x5f])
+        );
+        assert!(MacAddr::BROADCAST.is_broadcast());
+        assert!(MacAddr::BROADCAST.is_multicast());
+        assert!(!addr.is_multicast());
+    }
 }
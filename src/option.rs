@@ -0,0 +1,259 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, Result, SplittableBuffer},
+    constant::ConstValue,
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+use core::ops::BitAnd;
+use num_traits::Zero;
+
+/// An optional value preceded by a presence byte of `0` or `1`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Flagged;
+
+impl<T, B> Decoder<Option<T>, B> for Flagged
+where
+    B: SplittableBuffer,
+    T: TypeDecoder<B>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Option<T>, B> {
+        let (flag, buffer) = buffer.decode::<u8>()?;
+        match flag {
+            0 => Ok((None, buffer)),
+            1 => {
+                let (value, buffer) = buffer.decode()?;
+                Ok((Some(value), buffer))
+            }
+            _ => Err(BufferError::new(buffer, BufferErrorReason::NonCanonical)),
+        }
+    }
+}
+
+impl<T, B> Encoder<Option<T>, B> for Flagged
+where
+    B: EncoderBuffer,
+    T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, value: Option<T>, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(value.is_some() as u8)?;
+        let (_, buffer) = buffer.encode(value)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, T, B> Encoder<&'a Option<T>, B> for Flagged
+where
+    B: EncoderBuffer,
+    &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, value: &'a Option<T>, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(value.is_some() as u8)?;
+        let (_, buffer) = buffer.encode(value)?;
+        Ok(((), buffer))
+    }
+}
+
+/// An optional value that is absent when it equals `SENTINEL`
+///
+/// The sentinel is given as an `i128` so signed types can use negative values. It must
+/// be in range for the value type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NoneIf<const SENTINEL: i128>;
+
+impl<T, B, const SENTINEL: i128> Decoder<Option<T>, B> for NoneIf<SENTINEL>
+where
+    B: SplittableBuffer,
+    T: TypeDecoder<B> + ConstValue,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Option<T>, B> {
+        let (value, buffer) = buffer.decode::<T>()?;
+        if T::from_i128(SENTINEL) == Some(value) {
+            Ok((None, buffer))
+        } else {
+            Ok((Some(value), buffer))
+        }
+    }
+}
+
+impl<T, B, const SENTINEL: i128> Encoder<Option<T>, B> for NoneIf<SENTINEL>
+where
+    B: EncoderBuffer,
+    T: TypeEncoder<B> + ConstValue,
+{
+    #[inline(always)]
+    fn encode_into(self, value: Option<T>, buffer: B) -> Result<(), B> {
+        let sentinel = T::from_i128(SENTINEL);
+        let value = match value {
+            Some(value) if sentinel == Some(value) => Err("value collides with sentinel"),
+            Some(value) => Ok(value),
+            None => sentinel.ok_or("sentinel out of range"),
+        };
+        match value {
+            Ok(value) => {
+                let (_, buffer) = buffer.encode(value)?;
+                Ok(((), buffer))
+            }
+            Err(message) => Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue { message },
+            )),
+        }
+    }
+}
+
+impl<'a, T, B, const SENTINEL: i128> Encoder<&'a Option<T>, B> for NoneIf<SENTINEL>
+where
+    B: EncoderBuffer,
+    T: TypeEncoder<B> + ConstValue,
+{
+    #[inline(always)]
+    fn encode_into(self, value: &'a Option<T>, buffer: B) -> Result<(), B> {
+        self.encode_into(*value, buffer)
+    }
+}
+
+const PRESENCE_MISMATCH: BufferErrorReason = BufferErrorReason::InvalidValue {
+    message: "presence does not match flag",
+};
+
+/// An optional value whose presence is signaled by a previously decoded field
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PresentIf(pub bool);
+
+impl PresentIf {
+    /// Signals presence when any of the bits in `mask` are set in `flags`
+    #[inline(always)]
+    pub fn bits<F: BitAnd<Output = F> + Zero>(flags: F, mask: F) -> Self {
+        Self(!(flags & mask).is_zero())
+    }
+}
+
+impl<T, B> Decoder<Option<T>, B> for PresentIf
+where
+    B: SplittableBuffer,
+    T: TypeDecoder<B>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Option<T>, B> {
+        if self.0 {
+            let (value, buffer) = buffer.decode()?;
+            Ok((Some(value), buffer))
+        } else {
+            Ok((None, buffer))
+        }
+    }
+}
+
+impl<T, B> Encoder<Option<T>, B> for PresentIf
+where
+    B: EncoderBuffer,
+    T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, value: Option<T>, buffer: B) -> Result<(), B> {
+        if self.0 != value.is_some() {
+            return Err(BufferError::new(buffer, PRESENCE_MISMATCH));
+        }
+        let (_, buffer) = buffer.encode(value)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, T, B> Encoder<&'a Option<T>, B> for PresentIf
+where
+    B: EncoderBuffer,
+    &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, value: &'a Option<T>, buffer: B) -> Result<(), B> {
+        if self.0 != value.is_some() {
+            return Err(BufferError::new(buffer, PRESENCE_MISMATCH));
+        }
+        let (_, buffer) = buffer.encode(value)?;
+        Ok(((), buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flagged_test() {
+        let buffer = &[1u8, 0, 7, 0, 2][..];
+        let (value, buffer) = buffer.decode_with::<Option<u16>, _>(Flagged).unwrap();
+        assert_eq!(value, Some(7));
+        let (value, buffer) = buffer.decode_with::<Option<u16>, _>(Flagged).unwrap();
+        assert_eq!(value, None);
+        let err = buffer.decode_with::<Option<u16>, _>(Flagged).unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::NonCanonical);
+
+        let mut out = [0u8; 4];
+        let (_, rest) = (&mut out[..]).encode_with(Some(7u16), Flagged).unwrap();
+        let (_, _) = rest.encode_with(None::<u16>, Flagged).unwrap();
+        assert_eq!(out, [1, 0, 7, 0]);
+    }
+
+    #[test]
+    fn none_if_test() {
+        let buffer = &[0xffu8, 0xff, 0, 1][..];
+        let (value, buffer) = buffer
+            .decode_with::<Option<u16>, _>(NoneIf::<0xffff>)
+            .unwrap();
+        assert_eq!(value, None);
+        let (value, _) = buffer
+            .decode_with::<Option<u16>, _>(NoneIf::<0xffff>)
+            .unwrap();
+        assert_eq!(value, Some(1));
+
+        let mut out = [0u8; 2];
+        (&mut out[..])
+            .encode_with(None::<u16>, NoneIf::<0xffff>)
+            .unwrap();
+        assert_eq!(out, [0xff, 0xff]);
+        assert!((&mut out[..])
+            .encode_with(Some(0xffffu16), NoneIf::<0xffff>)
+            .is_err());
+        assert!((&mut out[..])
+            .encode_with(None::<u16>, NoneIf::<-1>)
+            .is_err());
+    }
+
+    #[test]
+    fn signed_none_if_test() {
+        let buffer = &[0xffu8, 0xff, 0xff, 0xff, 0, 0, 0, 2][..];
+        let (value, buffer) = buffer.decode_with::<Option<i32>, _>(NoneIf::<-1>).unwrap();
+        assert_eq!(value, None);
+        let (value, _) = buffer.decode_with::<Option<i32>, _>(NoneIf::<-1>).unwrap();
+        assert_eq!(value, Some(2));
+
+        let mut out = [0u8; 4];
+        (&mut out[..])
+            .encode_with(None::<i32>, NoneIf::<-1>)
+            .unwrap();
+        assert_eq!(out, [0xff; 4]);
+    }
+
+    #[test]
+    fn present_if_test() {
+        let buffer = &[0b10u8, 5][..];
+        let (flags, buffer) = buffer.decode::<u8>().unwrap();
+        let (a, buffer) = buffer
+            .decode_with::<Option<u8>, _>(PresentIf::bits(flags, 0b01))
+            .unwrap();
+        let (b, buffer) = buffer
+            .decode_with::<Option<u8>, _>(PresentIf::bits(flags, 0b10))
+            .unwrap();
+        assert_eq!((a, b), (None, Some(5)));
+        assert!(buffer.is_empty());
+
+        let mut out = [0u8; 1];
+        assert!((&mut out[..])
+            .encode_with(None::<u8>, PresentIf(true))
+            .is_err());
+    }
+}
@@ -0,0 +1,116 @@
+/// Accumulates the 16-bit ones' complement sum used by the Internet checksum (RFC 1071)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Checksum {
+    sum: u32,
+    // a trailing odd byte waiting for its partner
+    pending: Option<u8>,
+}
+
+impl Checksum {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            sum: 0,
+            pending: None,
+        }
+    }
+
+    #[inline]
+    pub fn add_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        let mut bytes = bytes;
+
+        if let Some(high) = self.pending.take() {
+            match bytes.split_first() {
+                Some((low, rest)) => {
+                    self.add_u16(u16::from_be_bytes([high, *low]));
+                    bytes = rest;
+                }
+                None => {
+                    self.pending = Some(high);
+                    return self;
+                }
+            }
+        }
+
+        let mut chunks = bytes.chunks_exact(2);
+        for chunk in &mut chunks {
+            self.add_u16(u16::from_be_bytes([chunk[0], chunk[1]]));
+        }
+        if let [byte] = chunks.remainder() {
+            self.pending = Some(*byte);
+        }
+
+        self
+    }
+
+    /// Adds a header as if its 16-bit checksum field at `offset` were zero
+    #[inline(always)]
+    pub fn add_header(&mut self, header: &[u8], offset: usize) -> &mut Self {
+        // skipping an even number of bytes keeps the remaining bytes aligned
+        self.add_bytes(&header[..offset])
+            .add_bytes(&header[offset + 2..])
+    }
+
+    #[inline(always)]
+    pub fn add_u16(&mut self, value: u16) -> &mut Self {
+        if self.pending.is_some() {
+            return self.add_bytes(&value.to_be_bytes());
+        }
+
+        self.sum += value as u32;
+        self.sum = (self.sum & 0xffff) + (self.sum >> 16);
+        self
+    }
+
+    #[inline(always)]
+    pub fn add_u32(&mut self, value: u32) -> &mut Self {
+        self.add_u16((value >> 16) as u16).add_u16(value as u16)
+    }
+
+    /// Returns the ones' complement of the sum, padding any odd trailing byte with zero
+    #[inline(always)]
+    pub fn finish(&self) -> u16 {
+        let mut checksum = *self;
+        if let Some(high) = checksum.pending.take() {
+            checksum.add_u16(u16::from_be_bytes([high, 0]));
+        }
+        !(checksum.sum as u16)
+    }
+}
+
+/// Computes the Internet checksum of `bytes`
+#[inline(always)]
+pub fn checksum(bytes: &[u8]) -> u16 {
+    Checksum::new().add_bytes(bytes).finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_test() {
+        // example from RFC 1071 section 3
+        let bytes = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+        assert_eq!(checksum(&bytes), !0xddf2);
+
+        // splitting the input at an odd offset doesn't change the result
+        let mut split = Checksum::new();
+        split.add_bytes(&bytes[..3]).add_bytes(&bytes[3..]);
+        assert_eq!(split.finish(), checksum(&bytes));
+    }
+
+    #[test]
+    fn odd_add_u16_test() {
+        // a word added after an odd number of bytes continues the byte stream
+        let mut actual = Checksum::new();
+        actual
+            .add_bytes(&[0x12])
+            .add_u16(0x3456)
+            .add_u32(0x789a_bcde);
+        assert_eq!(
+            actual.finish(),
+            checksum(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde])
+        );
+    }
+}
@@ -0,0 +1,450 @@
+use crate::{
+    buffer::{Result, SplittableBuffer},
+    constant::Const,
+    decode::{DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, TypeEncoder},
+    net::MacAddr,
+};
+use core::net::Ipv4Addr;
+use zerocopy::{AsBytes, FromBytes, Unaligned};
+
+/// Identifies the protocol carried in an Ethernet frame
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EtherType(pub u16);
+
+impl EtherType {
+    pub const IPV4: Self = Self(0x0800);
+    pub const ARP: Self = Self(0x0806);
+    pub const VLAN: Self = Self(0x8100);
+    pub const IPV6: Self = Self(0x86dd);
+    pub const QINQ: Self = Self(0x88a8);
+
+    /// Returns `true` if the value is an 802.1Q or 802.1ad tag protocol identifier
+    #[inline(always)]
+    pub const fn is_vlan(self) -> bool {
+        self.0 == Self::VLAN.0 || self.0 == Self::QINQ.0
+    }
+}
+
+impl<B: SplittableBuffer> TypeDecoder<B> for EtherType {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (value, buffer) = buffer.decode()?;
+        Ok((Self(value), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for EtherType {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.0.encode_type(buffer)
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &EtherType {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.0.encode_type(buffer)
+    }
+}
+
+/// A zero-copy Ethernet II header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct Header {
+    destination: [u8; 6],
+    source: [u8; 6],
+    ether_type: [u8; 2],
+}
+
+impl Header {
+    pub const LEN: usize = 14;
+
+    #[inline(always)]
+    pub fn destination(&self) -> MacAddr {
+        MacAddr(self.destination)
+    }
+
+    #[inline(always)]
+    pub fn set_destination(&mut self, addr: MacAddr) {
+        self.destination = addr.0;
+    }
+
+    #[inline(always)]
+    pub fn source(&self) -> MacAddr {
+        MacAddr(self.source)
+    }
+
+    #[inline(always)]
+    pub fn set_source(&mut self, addr: MacAddr) {
+        self.source = addr.0;
+    }
+
+    /// Returns the EtherType, or the tag protocol identifier if the frame is tagged
+    #[inline(always)]
+    pub fn ether_type(&self) -> EtherType {
+        EtherType(u16::from_be_bytes(self.ether_type))
+    }
+
+    #[inline(always)]
+    pub fn set_ether_type(&mut self, ether_type: EtherType) {
+        self.ether_type = ether_type.0.to_be_bytes();
+    }
+}
+
+/// A zero-copy 802.1Q tag following a tag protocol identifier
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct VlanTag {
+    tci: [u8; 2],
+    ether_type: [u8; 2],
+}
+
+impl VlanTag {
+    pub const LEN: usize = 4;
+
+    #[inline(always)]
+    pub fn vlan(&self) -> Vlan {
+        Vlan::from_tci(u16::from_be_bytes(self.tci))
+    }
+
+    #[inline(always)]
+    pub fn set_vlan(&mut self, vlan: Vlan) {
+        self.tci = vlan.to_tci().to_be_bytes();
+    }
+
+    /// Returns the EtherType of the payload, or the next tag protocol identifier
+    #[inline(always)]
+    pub fn ether_type(&self) -> EtherType {
+        EtherType(u16::from_be_bytes(self.ether_type))
+    }
+
+    #[inline(always)]
+    pub fn set_ether_type(&mut self, ether_type: EtherType) {
+        self.ether_type = ether_type.0.to_be_bytes();
+    }
+}
+
+/// The fields of an 802.1Q tag control information value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vlan {
+    /// Priority code point
+    pub pcp: u8,
+    /// Drop eligible indicator
+    pub dei: bool,
+    /// VLAN identifier
+    pub vid: u16,
+}
+
+impl Vlan {
+    #[inline(always)]
+    pub const fn from_tci(tci: u16) -> Self {
+        Self {
+            pcp: (tci >> 13) as u8,
+            dei: tci & 0x1000 != 0,
+            vid: tci & 0x0fff,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn to_tci(self) -> u16 {
+        ((self.pcp as u16 & 0x7) << 13) | ((self.dei as u16) << 12) | (self.vid & 0x0fff)
+    }
+}
+
+/// An owned Ethernet header, including any 802.1ad and 802.1Q tags
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Ethernet {
+    pub destination: MacAddr,
+    pub source: MacAddr,
+    /// The outer 802.1ad service tag
+    pub service_vlan: Option<Vlan>,
+    /// The 802.1Q customer tag
+    pub vlan: Option<Vlan>,
+    pub ether_type: EtherType,
+}
+
+impl<B: SplittableBuffer> TypeDecoder<B> for Ethernet {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (destination, buffer) = buffer.decode()?;
+        let (source, buffer) = buffer.decode()?;
+        let (mut ether_type, mut buffer) = buffer.decode::<EtherType>()?;
+
+        let mut service_vlan = None;
+        if ether_type == EtherType::QINQ {
+            let (tci, next) = buffer.decode()?;
+            service_vlan = Some(Vlan::from_tci(tci));
+            let (next_type, next) = next.decode()?;
+            ether_type = next_type;
+            buffer = next;
+        }
+
+        let mut vlan = None;
+        if ether_type == EtherType::VLAN {
+            let (tci, next) = buffer.decode()?;
+            vlan = Some(Vlan::from_tci(tci));
+            let (next_type, next) = next.decode()?;
+            ether_type = next_type;
+            buffer = next;
+        }
+
+        let value = Self {
+            destination,
+            source,
+            service_vlan,
+            vlan,
+            ether_type,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &Ethernet {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, mut buffer) = buffer.encode((self.destination, self.source))?;
+        if let Some(vlan) = self.service_vlan {
+            let (_, next) = buffer.encode((EtherType::QINQ, vlan.to_tci()))?;
+            buffer = next;
+        }
+        if let Some(vlan) = self.vlan {
+            let (_, next) = buffer.encode((EtherType::VLAN, vlan.to_tci()))?;
+            buffer = next;
+        }
+        let (_, buffer) = buffer.encode(self.ether_type)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for Ethernet {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+/// Identifies the kind of ARP message
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArpOperation(pub u16);
+
+impl ArpOperation {
+    pub const REQUEST: Self = Self(1);
+    pub const REPLY: Self = Self(2);
+}
+
+const ARP_HTYPE_ETHERNET: u16 = 1;
+
+/// A zero-copy ARP packet for IPv4 over Ethernet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct ArpPacket {
+    htype: [u8; 2],
+    ptype: [u8; 2],
+    hlen: u8,
+    plen: u8,
+    operation: [u8; 2],
+    sender_hw: [u8; 6],
+    sender_ip: [u8; 4],
+    target_hw: [u8; 6],
+    target_ip: [u8; 4],
+}
+
+impl ArpPacket {
+    pub const LEN: usize = 28;
+
+    /// Returns `true` if the packet describes Ethernet and IPv4 addresses
+    #[inline(always)]
+    pub fn is_ethernet_ipv4(&self) -> bool {
+        u16::from_be_bytes(self.htype) == ARP_HTYPE_ETHERNET
+            && u16::from_be_bytes(self.ptype) == EtherType::IPV4.0
+            && self.hlen == 6
+            && self.plen == 4
+    }
+
+    #[inline(always)]
+    pub fn operation(&self) -> ArpOperation {
+        ArpOperation(u16::from_be_bytes(self.operation))
+    }
+
+    #[inline(always)]
+    pub fn set_operation(&mut self, operation: ArpOperation) {
+        self.operation = operation.0.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn sender_hw(&self) -> MacAddr {
+        MacAddr(self.sender_hw)
+    }
+
+    #[inline(always)]
+    pub fn set_sender_hw(&mut self, addr: MacAddr) {
+        self.sender_hw = addr.0;
+    }
+
+    #[inline(always)]
+    pub fn sender_ip(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.sender_ip)
+    }
+
+    #[inline(always)]
+    pub fn set_sender_ip(&mut self, addr: Ipv4Addr) {
+        self.sender_ip = addr.octets();
+    }
+
+    #[inline(always)]
+    pub fn target_hw(&self) -> MacAddr {
+        MacAddr(self.target_hw)
+    }
+
+    #[inline(always)]
+    pub fn set_target_hw(&mut self, addr: MacAddr) {
+        self.target_hw = addr.0;
+    }
+
+    #[inline(always)]
+    pub fn target_ip(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.target_ip)
+    }
+
+    #[inline(always)]
+    pub fn set_target_ip(&mut self, addr: Ipv4Addr) {
+        self.target_ip = addr.octets();
+    }
+}
+
+/// An owned ARP packet for IPv4 over Ethernet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Arp {
+    pub operation: ArpOperation,
+    pub sender_hw: MacAddr,
+    pub sender_ip: Ipv4Addr,
+    pub target_hw: MacAddr,
+    pub target_ip: Ipv4Addr,
+}
+
+impl<B: SplittableBuffer> TypeDecoder<B> for Arp {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let ((), buffer) = buffer.decode_with(Const(ARP_HTYPE_ETHERNET))?;
+        let ((), buffer) = buffer.decode_with(Const(EtherType::IPV4.0))?;
+        let ((), buffer) = buffer.decode_with(Const(6u8))?;
+        let ((), buffer) = buffer.decode_with(Const(4u8))?;
+
+        let (operation, buffer) = buffer.decode()?;
+        let (sender_hw, buffer) = buffer.decode()?;
+        let (sender_ip, buffer) = buffer.decode()?;
+        let (target_hw, buffer) = buffer.decode()?;
+        let (target_ip, buffer) = buffer.decode()?;
+
+        let value = Self {
+            operation: ArpOperation(operation),
+            sender_hw,
+            sender_ip,
+            target_hw,
+            target_ip,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &Arp {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode((ARP_HTYPE_ETHERNET, EtherType::IPV4, 6u8, 4u8))?;
+        let (_, buffer) = buffer.encode(self.operation.0)?;
+        let (_, buffer) = buffer.encode((self.sender_hw, self.sender_ip))?;
+        let (_, buffer) = buffer.encode((self.target_hw, self.target_ip))?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for Arp {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zerocopy::{Mut, Ref};
+
+    const FRAME: [u8; 22] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // destination
+        0x00, 0x1b, 0x2c, 0x3d, 0x4e, 0x5f, // source
+        0x88, 0xa8, 0x00, 0x64, // service tag, vid 100
+        0x81, 0x00, 0x20, 0x0a, // customer tag, pcp 1, vid 10
+        0x08, 0x06, // ARP
+    ];
+
+    #[test]
+    fn header_ref_test() {
+        let buffer = &FRAME[..];
+        let (header, buffer) = buffer.decode::<Ref<Header, _>>().unwrap();
+        assert_eq!(header.destination(), MacAddr::BROADCAST);
+        assert_eq!(header.ether_type(), EtherType::QINQ);
+        assert!(header.ether_type().is_vlan());
+
+        let (outer, buffer) = buffer.decode::<Ref<VlanTag, _>>().unwrap();
+        assert_eq!(outer.vlan().vid, 100);
+        let (inner, _) = buffer.decode::<Ref<VlanTag, _>>().unwrap();
+        assert_eq!(
+            inner.vlan(),
+            Vlan {
+                pcp: 1,
+                dei: false,
+                vid: 10
+            }
+        );
+        assert_eq!(inner.ether_type(), EtherType::ARP);
+    }
+
+    #[test]
+    fn header_mut_test() {
+        let mut frame = FRAME;
+        let buffer = &mut frame[..];
+        let (mut header, _) = buffer.decode::<Mut<Header, _>>().unwrap();
+        header.set_source(MacAddr([2; 6]));
+        assert_eq!(&frame[6..12], &[2; 6]);
+    }
+
+    #[test]
+    fn ethernet_round_trip_test() {
+        let (ethernet, _) = (&FRAME[..]).decode::<Ethernet>().unwrap();
+        assert_eq!(ethernet.service_vlan.map(|vlan| vlan.vid), Some(100));
+        assert_eq!(ethernet.vlan.map(|vlan| vlan.vid), Some(10));
+        assert_eq!(ethernet.ether_type, EtherType::ARP);
+
+        let mut out = [0u8; 22];
+        (&mut out[..]).encode(ethernet).unwrap();
+        assert_eq!(out, FRAME);
+    }
+
+    #[test]
+    fn arp_round_trip_test() {
+        let arp = Arp {
+            operation: ArpOperation::REQUEST,
+            sender_hw: MacAddr([0x00, 0x1b, 0x2c, 0x3d, 0x4e, 0x5f]),
+            sender_ip: Ipv4Addr::new(192, 168, 0, 1),
+            target_hw: MacAddr::default(),
+            target_ip: Ipv4Addr::new(192, 168, 0, 2),
+        };
+
+        let mut out = [0u8; ArpPacket::LEN];
+        (&mut out[..]).encode(arp).unwrap();
+        assert_eq!(&out[..8], &[0, 1, 0x08, 0x00, 6, 4, 0, 1]);
+
+        let (actual, _) = (&out[..]).decode::<Arp>().unwrap();
+        assert_eq!(actual, arp);
+
+        let (packet, _) = (&out[..]).decode::<Ref<ArpPacket, _>>().unwrap();
+        assert!(packet.is_ethernet_ipv4());
+        assert_eq!(packet.target_ip(), arp.target_ip);
+
+        let (mut packet, _) = (&mut out[..]).decode::<Mut<ArpPacket, _>>().unwrap();
+        packet.set_operation(ArpOperation::REPLY);
+        assert_eq!(&out[6..8], &[0, 2]);
+    }
+}
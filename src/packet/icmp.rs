@@ -0,0 +1,470 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result},
+    decode::{Decoder, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+    packet::{
+        checksum::Checksum,
+        ip::{IpProtocol, PseudoHeader},
+    },
+};
+use zerocopy::{AsBytes, FromBytes, Unaligned};
+
+/// Identifies the kind of ICMPv4 message
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Icmpv4Type(pub u8);
+
+impl Icmpv4Type {
+    pub const ECHO_REPLY: Self = Self(0);
+    pub const DESTINATION_UNREACHABLE: Self = Self(3);
+    pub const REDIRECT: Self = Self(5);
+    pub const ECHO_REQUEST: Self = Self(8);
+    pub const TIME_EXCEEDED: Self = Self(11);
+    pub const PARAMETER_PROBLEM: Self = Self(12);
+}
+
+/// Identifies the kind of ICMPv6 message
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Icmpv6Type(pub u8);
+
+impl Icmpv6Type {
+    pub const DESTINATION_UNREACHABLE: Self = Self(1);
+    pub const PACKET_TOO_BIG: Self = Self(2);
+    pub const TIME_EXCEEDED: Self = Self(3);
+    pub const PARAMETER_PROBLEM: Self = Self(4);
+    pub const ECHO_REQUEST: Self = Self(128);
+    pub const ECHO_REPLY: Self = Self(129);
+    pub const ROUTER_SOLICITATION: Self = Self(133);
+    pub const ROUTER_ADVERTISEMENT: Self = Self(134);
+    pub const NEIGHBOR_SOLICITATION: Self = Self(135);
+    pub const NEIGHBOR_ADVERTISEMENT: Self = Self(136);
+}
+
+/// The identifier and sequence number of an echo request or reply
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Echo {
+    pub identifier: u16,
+    pub sequence: u16,
+}
+
+impl From<[u8; 4]> for Echo {
+    #[inline(always)]
+    fn from(rest: [u8; 4]) -> Self {
+        Self {
+            identifier: u16::from_be_bytes([rest[0], rest[1]]),
+            sequence: u16::from_be_bytes([rest[2], rest[3]]),
+        }
+    }
+}
+
+impl From<Echo> for [u8; 4] {
+    #[inline(always)]
+    fn from(echo: Echo) -> Self {
+        let [a, b] = echo.identifier.to_be_bytes();
+        let [c, d] = echo.sequence.to_be_bytes();
+        [a, b, c, d]
+    }
+}
+
+/// A zero-copy ICMPv4 or ICMPv6 header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct Header {
+    message_type: u8,
+    code: u8,
+    checksum: [u8; 2],
+    rest_of_header: [u8; 4],
+}
+
+const CHECKSUM_OFFSET: usize = 2;
+
+impl Header {
+    pub const LEN: usize = 8;
+
+    #[inline(always)]
+    pub fn message_type(&self) -> u8 {
+        self.message_type
+    }
+
+    #[inline(always)]
+    pub fn set_message_type(&mut self, message_type: u8) {
+        self.message_type = message_type;
+    }
+
+    #[inline(always)]
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+
+    #[inline(always)]
+    pub fn set_code(&mut self, code: u8) {
+        self.code = code;
+    }
+
+    #[inline(always)]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(self.checksum)
+    }
+
+    #[inline(always)]
+    pub fn set_checksum(&mut self, checksum: u16) {
+        self.checksum = checksum.to_be_bytes();
+    }
+
+    /// Returns the type-specific field following the checksum
+    #[inline(always)]
+    pub fn rest_of_header(&self) -> [u8; 4] {
+        self.rest_of_header
+    }
+
+    #[inline(always)]
+    pub fn set_rest_of_header(&mut self, rest: [u8; 4]) {
+        self.rest_of_header = rest;
+    }
+
+    /// Computes the checksum over the header and `payload`, ignoring the stored value
+    ///
+    /// ICMPv6 messages also cover the IPv6 pseudo-header, while ICMPv4 messages don't.
+    #[inline]
+    pub fn compute_checksum(&self, pseudo: Option<&PseudoHeader>, payload: &[u8]) -> u16 {
+        let mut checksum = match pseudo {
+            Some(pseudo) => pseudo.checksum(IpProtocol::ICMPV6, Self::LEN + payload.len()),
+            None => Checksum::new(),
+        };
+        checksum
+            .add_header(self.as_bytes(), CHECKSUM_OFFSET)
+            .add_bytes(payload)
+            .finish()
+    }
+
+    /// Stores the checksum computed over the header and `payload`
+    #[inline(always)]
+    pub fn fill_checksum(&mut self, pseudo: Option<&PseudoHeader>, payload: &[u8]) {
+        let checksum = self.compute_checksum(pseudo, payload);
+        self.set_checksum(checksum);
+    }
+}
+
+/// An owned ICMP message
+///
+/// The message extends to the end of the buffer, so decoding should be limited to the
+/// IP payload length.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Icmp<T, P> {
+    pub message_type: T,
+    pub code: u8,
+    /// The type-specific field following the checksum
+    pub rest_of_header: [u8; 4],
+    pub payload: P,
+}
+
+/// An ICMPv4 message, which carries its own checksum
+pub type Icmpv4<P> = Icmp<Icmpv4Type, P>;
+
+/// An ICMPv6 message, which is checksummed with the IPv6 pseudo-header
+pub type Icmpv6<P> = Icmp<Icmpv6Type, P>;
+
+impl<T, P> Icmp<T, P> {
+    /// Interprets the rest of the header as an echo identifier and sequence number
+    #[inline(always)]
+    pub fn echo(&self) -> Echo {
+        self.rest_of_header.into()
+    }
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<T, P: FiniteBuffer> Icmp<T, P> {
+    /// Returns the length of the message, including the header
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        Header::LEN + self.payload.len()
+    }
+
+    /// Returns `true` if the payload is empty
+    #[inline(always)]
+    pub fn payload_is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+}
+
+#[inline(always)]
+fn decode_unchecked<B: FiniteBuffer>(buffer: B) -> Result<Header, B> {
+    let (fixed, buffer) = buffer.checked_split(Header::LEN)?;
+    let mut header = Header::default();
+    header
+        .as_bytes_mut()
+        .copy_from_slice(fixed.as_less_safe_slice());
+    Ok((header, buffer))
+}
+
+#[inline(always)]
+fn decode_checked<B: FiniteBuffer>(buffer: B, pseudo: Option<&PseudoHeader>) -> Result<Header, B> {
+    let (header, buffer) = decode_unchecked(buffer)?;
+
+    let expected = header.compute_checksum(pseudo, buffer.as_less_safe_slice());
+    let actual = header.checksum();
+    if expected != actual {
+        return Err(BufferError::new(
+            buffer,
+            BufferErrorReason::ChecksumMismatch {
+                expected: expected as u64,
+                actual: actual as u64,
+            },
+        ));
+    }
+
+    Ok((header, buffer))
+}
+
+#[inline(always)]
+fn encode_checked<T: Copy + Into<u8>, P: FiniteBuffer, B: EncoderBuffer>(
+    icmp: &Icmp<T, P>,
+    pseudo: Option<&PseudoHeader>,
+    buffer: B,
+) -> Result<(), B> {
+    let payload = icmp.payload.as_less_safe_slice();
+
+    let mut header = Header::default();
+    header.set_message_type(icmp.message_type.into());
+    header.set_code(icmp.code);
+    header.set_rest_of_header(icmp.rest_of_header);
+    header.fill_checksum(pseudo, payload);
+
+    let (_, buffer) = buffer.encode_bytes(header.as_bytes())?;
+    let (_, buffer) = buffer.encode_bytes(payload)?;
+    Ok(((), buffer))
+}
+
+#[inline(always)]
+fn from_parts<T: From<u8>, P>(header: Header, payload: P) -> Icmp<T, P> {
+    Icmp {
+        message_type: header.message_type().into(),
+        code: header.code(),
+        rest_of_header: header.rest_of_header(),
+        payload,
+    }
+}
+
+macro_rules! message_type {
+    ($ty:ident) => {
+        impl From<u8> for $ty {
+            #[inline(always)]
+            fn from(value: u8) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$ty> for u8 {
+            #[inline(always)]
+            fn from(value: $ty) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+message_type!(Icmpv4Type);
+message_type!(Icmpv6Type);
+
+/// Decodes the message without verifying the checksum
+impl<B: FiniteBuffer> TypeDecoder<B> for Icmpv4<B> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (header, buffer) = decode_unchecked(buffer)?;
+        let (payload, buffer) = buffer.consume();
+        Ok((from_parts(header, payload), buffer))
+    }
+}
+
+/// Decodes an ICMPv4 message and verifies its checksum
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Verified;
+
+impl<B: FiniteBuffer> Decoder<Icmpv4<B>, B> for Verified {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Icmpv4<B>, B> {
+        let (header, buffer) = decode_checked(buffer, None)?;
+        let (payload, buffer) = buffer.consume();
+        Ok((from_parts(header, payload), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, P: FiniteBuffer> TypeEncoder<B> for &Icmpv4<P> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        encode_checked(self, None, buffer)
+    }
+}
+
+impl<B: EncoderBuffer, P: FiniteBuffer> TypeEncoder<B> for Icmpv4<P> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+/// Decodes the message without verifying the checksum
+impl<B: FiniteBuffer> TypeDecoder<B> for Icmpv6<B> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (header, buffer) = decode_unchecked(buffer)?;
+        let (payload, buffer) = buffer.consume();
+        Ok((from_parts(header, payload), buffer))
+    }
+}
+
+impl<B: FiniteBuffer> Decoder<Icmpv6<B>, B> for PseudoHeader {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Icmpv6<B>, B> {
+        let (header, buffer) = decode_checked(buffer, Some(&self))?;
+        let (payload, buffer) = buffer.consume();
+        Ok((from_parts(header, payload), buffer))
+    }
+}
+
+impl<'a, B: EncoderBuffer, P: FiniteBuffer> Encoder<&'a Icmpv6<P>, B> for PseudoHeader {
+    #[inline(always)]
+    fn encode_into(self, icmp: &'a Icmpv6<P>, buffer: B) -> Result<(), B> {
+        encode_checked(icmp, Some(&self), buffer)
+    }
+}
+
+impl<B: EncoderBuffer, P: FiniteBuffer> Encoder<Icmpv6<P>, B> for PseudoHeader {
+    #[inline(always)]
+    fn encode_into(self, icmp: Icmpv6<P>, buffer: B) -> Result<(), B> {
+        self.encode_into(&icmp, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode::DecoderBuffer,
+        packet::{ipv4::Ipv4, ipv6::Ipv6},
+        zerocopy::{Mut, Ref},
+    };
+    use core::net::{Ipv4Addr, Ipv6Addr};
+
+    const ECHO_REQUEST: [u8; 12] = [
+        0x08, 0x00, 0x33, 0x37, // echo request, checksum
+        0x00, 0x01, 0x00, 0x01, // identifier, sequence
+        0x61, 0x62, 0x63, 0x64, // payload
+    ];
+
+    #[test]
+    fn icmpv4_test() {
+        let (icmp, _) = (&ECHO_REQUEST[..]).decode::<Icmpv4<_>>().unwrap();
+        assert_eq!(icmp.message_type, Icmpv4Type::ECHO_REQUEST);
+        assert_eq!(
+            icmp.echo(),
+            Echo {
+                identifier: 1,
+                sequence: 1
+            }
+        );
+        assert_eq!(icmp.payload, b"abcd");
+
+        let mut out = [0u8; 12];
+        (&mut out[..]).encode(icmp).unwrap();
+        assert_eq!(out, ECHO_REQUEST);
+
+        let (verified, _) = (&ECHO_REQUEST[..]).decode_with(Verified).unwrap();
+        assert_eq!(icmp, verified);
+
+        let mut corrupt = ECHO_REQUEST;
+        corrupt[11] ^= 1;
+        assert!((&corrupt[..]).decode::<Icmpv4<_>>().is_ok());
+        let err = (&corrupt[..])
+            .decode_with::<Icmpv4<_>, _>(Verified)
+            .unwrap_err();
+        assert!(matches!(
+            err.reason,
+            BufferErrorReason::ChecksumMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn header_ref_test() {
+        let (header, payload) = (&ECHO_REQUEST[..]).decode::<Ref<Header, _>>().unwrap();
+        assert_eq!(header.message_type(), Icmpv4Type::ECHO_REQUEST.0);
+        assert_eq!(header.compute_checksum(None, payload), header.checksum());
+
+        let mut message = ECHO_REQUEST;
+        let (mut header, payload) = (&mut message[..]).decode::<Mut<Header, _>>().unwrap();
+        header.set_message_type(Icmpv4Type::ECHO_REPLY.0);
+        header.fill_checksum(None, payload);
+        let (reply, _) = (&message[..])
+            .decode_with::<Icmpv4<_>, _>(Verified)
+            .unwrap();
+        assert_eq!(reply.message_type, Icmpv4Type::ECHO_REPLY);
+    }
+
+    #[test]
+    fn ipv4_packet_test() {
+        let icmp = Icmpv4 {
+            message_type: Icmpv4Type::ECHO_REQUEST,
+            code: 0,
+            rest_of_header: Echo {
+                identifier: 1,
+                sequence: 1,
+            }
+            .into(),
+            payload: &b"abcd"[..],
+        };
+        let ipv4 = Ipv4 {
+            dscp: 0,
+            ecn: 0,
+            identification: 0,
+            dont_fragment: false,
+            more_fragments: false,
+            fragment_offset: 0,
+            ttl: 64,
+            protocol: IpProtocol::ICMP,
+            source: Ipv4Addr::LOCALHOST,
+            destination: Ipv4Addr::LOCALHOST,
+            options: Default::default(),
+            payload_len: icmp.len() as u16,
+        };
+
+        let mut out = [0u8; 32];
+        let (_, buffer) = (&mut out[..]).encode(ipv4).unwrap();
+        buffer.encode(icmp).unwrap();
+        assert_eq!(&out[20..], &ECHO_REQUEST[..]);
+    }
+
+    #[test]
+    fn icmpv6_test() {
+        let icmp = Icmpv6 {
+            message_type: Icmpv6Type::ECHO_REQUEST,
+            code: 0,
+            rest_of_header: [0, 7, 0, 1],
+            payload: &b"ping"[..],
+        };
+        let ipv6 = Ipv6 {
+            traffic_class: 0,
+            flow_label: 0,
+            payload_len: icmp.len() as u16,
+            next_header: IpProtocol::ICMPV6,
+            hop_limit: 64,
+            source: Ipv6Addr::LOCALHOST,
+            destination: Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1),
+        };
+
+        let mut out = [0u8; 52];
+        let (_, buffer) = (&mut out[..]).encode(ipv6).unwrap();
+        buffer
+            .encode_with(&icmp, PseudoHeader::from(&ipv6))
+            .unwrap();
+
+        let (ipv6, buffer) = (&out[..]).decode::<Ipv6>().unwrap();
+        let pseudo = PseudoHeader::from(&ipv6);
+        let (actual, _): (Icmpv6<_>, _) = buffer.decode_with(pseudo).unwrap();
+        assert_eq!(actual, icmp);
+        assert_eq!(actual.echo().identifier, 7);
+
+        // the same bytes don't verify without the pseudo-header
+        assert!((&out[40..]).decode_with::<Icmpv4<_>, _>(Verified).is_err());
+        let (unverified, _) = (&out[40..]).decode::<Icmpv6<_>>().unwrap();
+        assert_eq!(unverified, icmp);
+    }
+}
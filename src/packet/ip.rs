@@ -0,0 +1,225 @@
+use crate::{
+    buffer::{Result, SplittableBuffer},
+    decode::{DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, TypeEncoder},
+    packet::{checksum::Checksum, ipv4, ipv6},
+};
+use core::net::{Ipv4Addr, Ipv6Addr};
+
+/// Identifies the protocol carried in an IP packet or the next IPv6 header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpProtocol(pub u8);
+
+impl IpProtocol {
+    pub const HOP_BY_HOP: Self = Self(0);
+    pub const ICMP: Self = Self(1);
+    pub const TCP: Self = Self(6);
+    pub const UDP: Self = Self(17);
+    pub const ROUTING: Self = Self(43);
+    pub const FRAGMENT: Self = Self(44);
+    pub const ICMPV6: Self = Self(58);
+    pub const NO_NEXT: Self = Self(59);
+    pub const DESTINATION_OPTIONS: Self = Self(60);
+
+    /// Returns `true` if the value identifies an IPv6 extension header
+    #[inline(always)]
+    pub const fn is_ipv6_extension(self) -> bool {
+        matches!(self.0, 0 | 43 | 44 | 60)
+    }
+}
+
+impl<B: SplittableBuffer> TypeDecoder<B> for IpProtocol {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (value, buffer) = buffer.decode()?;
+        Ok((Self(value), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for IpProtocol {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.0.encode_type(buffer)
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &IpProtocol {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.0.encode_type(buffer)
+    }
+}
+
+/// The raw options of an IPv4 or TCP header
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Options {
+    len: u8,
+    bytes: [u8; Options::MAX_LEN],
+}
+
+impl Default for Options {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            len: 0,
+            bytes: [0; Self::MAX_LEN],
+        }
+    }
+}
+
+impl Options {
+    pub const MAX_LEN: usize = 40;
+
+    /// Returns `None` if `bytes` is longer than 40 bytes or not a multiple of 4
+    #[inline]
+    pub fn new(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() > Self::MAX_LEN || !bytes.len().is_multiple_of(4) {
+            return None;
+        }
+
+        let mut options = Self {
+            len: bytes.len() as u8,
+            ..Default::default()
+        };
+        options.bytes[..bytes.len()].copy_from_slice(bytes);
+        Some(options)
+    }
+
+    /// Pads `bytes` to a multiple of 4 with end of option list markers
+    ///
+    /// Returns `None` if the padded options are longer than 40 bytes.
+    #[inline]
+    pub fn new_padded(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() > Self::MAX_LEN {
+            return None;
+        }
+
+        let mut options = Self {
+            len: (bytes.len().div_ceil(4) * 4) as u8,
+            ..Default::default()
+        };
+        options.bytes[..bytes.len()].copy_from_slice(bytes);
+        Some(options)
+    }
+
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// The source and destination addresses covered by an upper-layer checksum
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PseudoHeader {
+    Ipv4 {
+        source: Ipv4Addr,
+        destination: Ipv4Addr,
+    },
+    Ipv6 {
+        source: Ipv6Addr,
+        destination: Ipv6Addr,
+    },
+}
+
+impl PseudoHeader {
+    /// Returns a checksum seeded with the pseudo-header for an upper-layer packet of `len` bytes
+    #[inline]
+    pub fn checksum(&self, protocol: IpProtocol, len: usize) -> Checksum {
+        let mut checksum = Checksum::new();
+        match self {
+            Self::Ipv4 {
+                source,
+                destination,
+            } => checksum
+                .add_bytes(&source.octets())
+                .add_bytes(&destination.octets()),
+            Self::Ipv6 {
+                source,
+                destination,
+            } => checksum
+                .add_bytes(&source.octets())
+                .add_bytes(&destination.octets()),
+        };
+        checksum.add_u32(len as u32).add_u16(protocol.0 as u16);
+        checksum
+    }
+}
+
+impl From<&ipv4::Ipv4> for PseudoHeader {
+    #[inline(always)]
+    fn from(header: &ipv4::Ipv4) -> Self {
+        Self::Ipv4 {
+            source: header.source,
+            destination: header.destination,
+        }
+    }
+}
+
+impl From<&ipv4::Header> for PseudoHeader {
+    #[inline(always)]
+    fn from(header: &ipv4::Header) -> Self {
+        Self::Ipv4 {
+            source: header.source(),
+            destination: header.destination(),
+        }
+    }
+}
+
+impl From<&ipv6::Ipv6> for PseudoHeader {
+    #[inline(always)]
+    fn from(header: &ipv6::Ipv6) -> Self {
+        Self::Ipv6 {
+            source: header.source,
+            destination: header.destination,
+        }
+    }
+}
+
+impl From<&ipv6::Header> for PseudoHeader {
+    #[inline(always)]
+    fn from(header: &ipv6::Header) -> Self {
+        Self::Ipv6 {
+            source: header.source(),
+            destination: header.destination(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_test() {
+        let options = Options::new_padded(&[2, 4, 5, 0xb4, 1, 3, 3, 7]).unwrap();
+        assert_eq!(options.len(), 8);
+
+        let options = Options::new_padded(&[1, 3, 3, 7, 4, 2]).unwrap();
+        assert_eq!(options.as_bytes(), &[1, 3, 3, 7, 4, 2, 0, 0]);
+
+        assert!(Options::new(&[1, 3, 3]).is_none());
+        assert!(Options::new_padded(&[1; 41]).is_none());
+    }
+
+    #[test]
+    fn pseudo_header_test() {
+        // the IPv4 pseudo-header sums the protocol and length as separate 16-bit words
+        let pseudo = PseudoHeader::Ipv4 {
+            source: Ipv4Addr::new(192, 168, 0, 1),
+            destination: Ipv4Addr::new(192, 168, 0, 199),
+        };
+        let expected =
+            crate::packet::checksum::checksum(&[192, 168, 0, 1, 192, 168, 0, 199, 0, 17, 0, 12]);
+        assert_eq!(pseudo.checksum(IpProtocol::UDP, 12).finish(), expected);
+    }
+}
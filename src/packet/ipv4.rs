@@ -0,0 +1,472 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
+    decode::TypeDecoder,
+    encode::{EncoderBuffer, TypeEncoder},
+    packet::{
+        checksum::Checksum,
+        ip::{IpProtocol, Options},
+    },
+};
+use core::net::Ipv4Addr;
+use zerocopy::{AsBytes, FromBytes, Unaligned};
+
+/// A zero-copy IPv4 header, not including any options
+///
+/// The options, if any, immediately follow the header and are `options_len` bytes long.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct Header {
+    version_ihl: u8,
+    dscp_ecn: u8,
+    total_len: [u8; 2],
+    identification: [u8; 2],
+    flags_fragment: [u8; 2],
+    ttl: u8,
+    protocol: u8,
+    checksum: [u8; 2],
+    source: [u8; 4],
+    destination: [u8; 4],
+}
+
+const DONT_FRAGMENT: u16 = 0x4000;
+const MORE_FRAGMENTS: u16 = 0x2000;
+const FRAGMENT_OFFSET_MASK: u16 = 0x1fff;
+
+impl Header {
+    pub const LEN: usize = 20;
+
+    #[inline(always)]
+    pub fn version(&self) -> u8 {
+        self.version_ihl >> 4
+    }
+
+    #[inline(always)]
+    pub fn set_version(&mut self, version: u8) {
+        self.version_ihl = (version << 4) | (self.version_ihl & 0x0f);
+    }
+
+    /// Returns the header length in 32-bit words
+    #[inline(always)]
+    pub fn ihl(&self) -> u8 {
+        self.version_ihl & 0x0f
+    }
+
+    #[inline(always)]
+    pub fn set_ihl(&mut self, ihl: u8) {
+        self.version_ihl = (self.version_ihl & 0xf0) | (ihl & 0x0f);
+    }
+
+    /// Returns the header length in bytes, including options
+    #[inline(always)]
+    pub fn header_len(&self) -> usize {
+        self.ihl() as usize * 4
+    }
+
+    /// Returns the length of the options following the header
+    ///
+    /// A malformed header with an IHL smaller than 5 has no options.
+    #[inline(always)]
+    pub fn options_len(&self) -> usize {
+        self.header_len().saturating_sub(Self::LEN)
+    }
+
+    #[inline(always)]
+    pub fn dscp(&self) -> u8 {
+        self.dscp_ecn >> 2
+    }
+
+    #[inline(always)]
+    pub fn set_dscp(&mut self, dscp: u8) {
+        self.dscp_ecn = (dscp << 2) | (self.dscp_ecn & 0x03);
+    }
+
+    #[inline(always)]
+    pub fn ecn(&self) -> u8 {
+        self.dscp_ecn & 0x03
+    }
+
+    #[inline(always)]
+    pub fn set_ecn(&mut self, ecn: u8) {
+        self.dscp_ecn = (self.dscp_ecn & 0xfc) | (ecn & 0x03);
+    }
+
+    /// Returns the length of the entire packet, including the header
+    #[inline(always)]
+    pub fn total_len(&self) -> u16 {
+        u16::from_be_bytes(self.total_len)
+    }
+
+    #[inline(always)]
+    pub fn set_total_len(&mut self, len: u16) {
+        self.total_len = len.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn identification(&self) -> u16 {
+        u16::from_be_bytes(self.identification)
+    }
+
+    #[inline(always)]
+    pub fn set_identification(&mut self, identification: u16) {
+        self.identification = identification.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn dont_fragment(&self) -> bool {
+        self.flags_fragment() & DONT_FRAGMENT != 0
+    }
+
+    #[inline(always)]
+    pub fn set_dont_fragment(&mut self, enabled: bool) {
+        self.set_flag(DONT_FRAGMENT, enabled)
+    }
+
+    #[inline(always)]
+    pub fn more_fragments(&self) -> bool {
+        self.flags_fragment() & MORE_FRAGMENTS != 0
+    }
+
+    #[inline(always)]
+    pub fn set_more_fragments(&mut self, enabled: bool) {
+        self.set_flag(MORE_FRAGMENTS, enabled)
+    }
+
+    /// Returns the fragment offset in units of 8 bytes
+    #[inline(always)]
+    pub fn fragment_offset(&self) -> u16 {
+        self.flags_fragment() & FRAGMENT_OFFSET_MASK
+    }
+
+    #[inline(always)]
+    pub fn set_fragment_offset(&mut self, offset: u16) {
+        let value =
+            (self.flags_fragment() & !FRAGMENT_OFFSET_MASK) | (offset & FRAGMENT_OFFSET_MASK);
+        self.flags_fragment = value.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn ttl(&self) -> u8 {
+        self.ttl
+    }
+
+    #[inline(always)]
+    pub fn set_ttl(&mut self, ttl: u8) {
+        self.ttl = ttl;
+    }
+
+    #[inline(always)]
+    pub fn protocol(&self) -> IpProtocol {
+        IpProtocol(self.protocol)
+    }
+
+    #[inline(always)]
+    pub fn set_protocol(&mut self, protocol: IpProtocol) {
+        self.protocol = protocol.0;
+    }
+
+    #[inline(always)]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(self.checksum)
+    }
+
+    #[inline(always)]
+    pub fn set_checksum(&mut self, checksum: u16) {
+        self.checksum = checksum.to_be_bytes();
+    }
+
+    /// Computes the header checksum over the header and `options`, ignoring the stored value
+    #[inline]
+    pub fn compute_checksum(&self, options: &[u8]) -> u16 {
+        Checksum::new()
+            .add_header(self.as_bytes(), 10)
+            .add_bytes(options)
+            .finish()
+    }
+
+    /// Stores the checksum computed over the header and `options`
+    #[inline(always)]
+    pub fn fill_checksum(&mut self, options: &[u8]) {
+        let checksum = self.compute_checksum(options);
+        self.set_checksum(checksum);
+    }
+
+    /// Returns `true` if the stored checksum matches the header and `options`
+    #[inline(always)]
+    pub fn verify_checksum(&self, options: &[u8]) -> bool {
+        self.compute_checksum(options) == self.checksum()
+    }
+
+    #[inline(always)]
+    pub fn source(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.source)
+    }
+
+    #[inline(always)]
+    pub fn set_source(&mut self, addr: Ipv4Addr) {
+        self.source = addr.octets();
+    }
+
+    #[inline(always)]
+    pub fn destination(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.destination)
+    }
+
+    #[inline(always)]
+    pub fn set_destination(&mut self, addr: Ipv4Addr) {
+        self.destination = addr.octets();
+    }
+
+    #[inline(always)]
+    fn flags_fragment(&self) -> u16 {
+        u16::from_be_bytes(self.flags_fragment)
+    }
+
+    #[inline(always)]
+    fn set_flag(&mut self, flag: u16, enabled: bool) {
+        let mut value = self.flags_fragment() & !flag;
+        if enabled {
+            value |= flag;
+        }
+        self.flags_fragment = value.to_be_bytes();
+    }
+}
+
+/// An owned IPv4 header
+///
+/// Decoding verifies the header checksum and encoding computes it. The payload is not
+/// part of the header and is left in the buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ipv4 {
+    pub dscp: u8,
+    pub ecn: u8,
+    pub identification: u16,
+    pub dont_fragment: bool,
+    pub more_fragments: bool,
+    /// The fragment offset in units of 8 bytes
+    pub fragment_offset: u16,
+    pub ttl: u8,
+    pub protocol: IpProtocol,
+    pub source: Ipv4Addr,
+    pub destination: Ipv4Addr,
+    pub options: Options,
+    /// The length of the payload following the header
+    pub payload_len: u16,
+}
+
+impl Ipv4 {
+    /// Returns the header length in bytes, including options
+    #[inline(always)]
+    pub fn header_len(&self) -> usize {
+        Header::LEN + self.options.len()
+    }
+}
+
+impl<B: SplittableBuffer> TypeDecoder<B> for Ipv4 {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (fixed, buffer) = buffer.checked_split(Header::LEN)?;
+        let mut header = Header::default();
+        header
+            .as_bytes_mut()
+            .copy_from_slice(fixed.as_less_safe_slice());
+
+        if header.version() != 4 {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "invalid ip version",
+                },
+            ));
+        }
+
+        let header_len = header.header_len();
+        if header_len < Header::LEN {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "invalid header length",
+                },
+            ));
+        }
+
+        let payload_len = match header.total_len().checked_sub(header_len as u16) {
+            Some(len) => len,
+            None => {
+                return Err(BufferError::new(
+                    buffer,
+                    BufferErrorReason::InvalidValue {
+                        message: "total length is shorter than the header",
+                    },
+                ))
+            }
+        };
+
+        let (options, buffer) = buffer.checked_split(header.options_len())?;
+        let options = options.as_less_safe_slice();
+
+        let expected = header.compute_checksum(options);
+        let actual = header.checksum();
+        if expected != actual {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::ChecksumMismatch {
+                    expected: expected as u64,
+                    actual: actual as u64,
+                },
+            ));
+        }
+
+        let options = match Options::new(options) {
+            Some(options) => options,
+            None => unreachable!("the IHL limits the options to 40 bytes in 32-bit words"),
+        };
+
+        let value = Self {
+            dscp: header.dscp(),
+            ecn: header.ecn(),
+            identification: header.identification(),
+            dont_fragment: header.dont_fragment(),
+            more_fragments: header.more_fragments(),
+            fragment_offset: header.fragment_offset(),
+            ttl: header.ttl(),
+            protocol: header.protocol(),
+            source: header.source(),
+            destination: header.destination(),
+            options,
+            payload_len,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &Ipv4 {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let header_len = self.header_len();
+        let total_len = match (header_len as u16).checked_add(self.payload_len) {
+            Some(len) => len,
+            None => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+
+        let mut header = Header::default();
+        header.set_version(4);
+        header.set_ihl((header_len / 4) as u8);
+        header.set_dscp(self.dscp);
+        header.set_ecn(self.ecn);
+        header.set_total_len(total_len);
+        header.set_identification(self.identification);
+        header.set_dont_fragment(self.dont_fragment);
+        header.set_more_fragments(self.more_fragments);
+        header.set_fragment_offset(self.fragment_offset);
+        header.set_ttl(self.ttl);
+        header.set_protocol(self.protocol);
+        header.set_source(self.source);
+        header.set_destination(self.destination);
+        header.fill_checksum(self.options.as_bytes());
+
+        let (_, buffer) = buffer.encode_bytes(header.as_bytes())?;
+        let (_, buffer) = buffer.encode_bytes(self.options.as_bytes())?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for Ipv4 {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode::DecoderBuffer,
+        zerocopy::{Mut, Ref},
+    };
+
+    const HEADER: [u8; 20] = [
+        0x45, 0x00, 0x00, 0x73, // version, IHL, DSCP, ECN, total length
+        0x00, 0x00, 0x40, 0x00, // identification, don't fragment
+        0x40, 0x11, 0xb8, 0x61, // TTL, UDP, checksum
+        0xc0, 0xa8, 0x00, 0x01, // source
+        0xc0, 0xa8, 0x00, 0xc7, // destination
+    ];
+
+    #[test]
+    fn header_ref_test() {
+        let buffer = &HEADER[..];
+        let (header, _) = buffer.decode::<Ref<Header, _>>().unwrap();
+        assert_eq!(header.version(), 4);
+        assert_eq!(header.header_len(), Header::LEN);
+        assert_eq!(header.options_len(), 0);
+        assert_eq!(header.total_len(), 0x73);
+        assert!(header.dont_fragment());
+        assert!(!header.more_fragments());
+        assert_eq!(header.protocol(), IpProtocol::UDP);
+        assert_eq!(header.source(), Ipv4Addr::new(192, 168, 0, 1));
+        assert!(header.verify_checksum(&[]));
+    }
+
+    #[test]
+    fn header_mut_test() {
+        let mut packet = HEADER;
+        let buffer = &mut packet[..];
+        let (mut header, _) = buffer.decode::<Mut<Header, _>>().unwrap();
+        header.set_ttl(1);
+        assert!(!header.verify_checksum(&[]));
+        header.fill_checksum(&[]);
+        assert!(header.verify_checksum(&[]));
+        assert_eq!(packet[8], 1);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let (ipv4, buffer) = (&HEADER[..]).decode::<Ipv4>().unwrap();
+        assert!(buffer.is_empty());
+        assert_eq!(ipv4.payload_len, 0x73 - 20);
+        assert_eq!(ipv4.ttl, 64);
+
+        let mut out = [0u8; 20];
+        (&mut out[..]).encode(ipv4).unwrap();
+        assert_eq!(out, HEADER);
+
+        let mut corrupt = HEADER;
+        corrupt[11] ^= 1;
+        let err = (&corrupt[..]).decode::<Ipv4>().unwrap_err();
+        assert_eq!(
+            err.reason,
+            BufferErrorReason::ChecksumMismatch {
+                expected: 0xb861,
+                actual: 0xb860,
+            }
+        );
+    }
+
+    #[test]
+    fn options_test() {
+        let ipv4 = Ipv4 {
+            options: Options::new(&[0x94, 0x04, 0x00, 0x00]).unwrap(),
+            ..(&HEADER[..]).decode::<Ipv4>().unwrap().0
+        };
+        assert!(Options::new(&[1, 2, 3]).is_none());
+
+        let mut out = [0u8; 24];
+        (&mut out[..]).encode(ipv4).unwrap();
+        assert_eq!(out[0], 0x46);
+        assert_eq!(&out[20..], &[0x94, 0x04, 0x00, 0x00]);
+
+        let (header, options) = (&out[..]).decode::<Ref<Header, _>>().unwrap();
+        assert_eq!(header.options_len(), 4);
+        assert!(header.verify_checksum(options));
+
+        let (actual, _) = (&out[..]).decode::<Ipv4>().unwrap();
+        assert_eq!(actual, ipv4);
+
+        let err = (&out[..20]).decode::<Ipv4>().unwrap_err();
+        assert!(matches!(
+            err.reason,
+            BufferErrorReason::UnexpectedEof { .. }
+        ));
+    }
+}
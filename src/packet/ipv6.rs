@@ -0,0 +1,450 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
+    decode::{DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, TypeEncoder},
+    packet::ip::IpProtocol,
+};
+use core::net::Ipv6Addr;
+use zerocopy::{AsBytes, FromBytes, Unaligned};
+
+const FLOW_LABEL_MASK: u32 = 0x000f_ffff;
+
+/// A zero-copy IPv6 fixed header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct Header {
+    version_class_flow: [u8; 4],
+    payload_len: [u8; 2],
+    next_header: u8,
+    hop_limit: u8,
+    source: [u8; 16],
+    destination: [u8; 16],
+}
+
+impl Header {
+    pub const LEN: usize = 40;
+
+    #[inline(always)]
+    pub fn version(&self) -> u8 {
+        self.version_class_flow[0] >> 4
+    }
+
+    #[inline(always)]
+    pub fn set_version(&mut self, version: u8) {
+        let value = (self.version_class_flow() & 0x0fff_ffff) | ((version as u32 & 0xf) << 28);
+        self.version_class_flow = value.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn traffic_class(&self) -> u8 {
+        (self.version_class_flow() >> 20) as u8
+    }
+
+    #[inline(always)]
+    pub fn set_traffic_class(&mut self, traffic_class: u8) {
+        let value = (self.version_class_flow() & 0xf00f_ffff) | ((traffic_class as u32) << 20);
+        self.version_class_flow = value.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn flow_label(&self) -> u32 {
+        self.version_class_flow() & FLOW_LABEL_MASK
+    }
+
+    #[inline(always)]
+    pub fn set_flow_label(&mut self, flow_label: u32) {
+        let value = (self.version_class_flow() & !FLOW_LABEL_MASK) | (flow_label & FLOW_LABEL_MASK);
+        self.version_class_flow = value.to_be_bytes();
+    }
+
+    /// Returns the length of everything following the fixed header, including extension headers
+    #[inline(always)]
+    pub fn payload_len(&self) -> u16 {
+        u16::from_be_bytes(self.payload_len)
+    }
+
+    #[inline(always)]
+    pub fn set_payload_len(&mut self, len: u16) {
+        self.payload_len = len.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn next_header(&self) -> IpProtocol {
+        IpProtocol(self.next_header)
+    }
+
+    #[inline(always)]
+    pub fn set_next_header(&mut self, next_header: IpProtocol) {
+        self.next_header = next_header.0;
+    }
+
+    #[inline(always)]
+    pub fn hop_limit(&self) -> u8 {
+        self.hop_limit
+    }
+
+    #[inline(always)]
+    pub fn set_hop_limit(&mut self, hop_limit: u8) {
+        self.hop_limit = hop_limit;
+    }
+
+    #[inline(always)]
+    pub fn source(&self) -> Ipv6Addr {
+        Ipv6Addr::from(self.source)
+    }
+
+    #[inline(always)]
+    pub fn set_source(&mut self, addr: Ipv6Addr) {
+        self.source = addr.octets();
+    }
+
+    #[inline(always)]
+    pub fn destination(&self) -> Ipv6Addr {
+        Ipv6Addr::from(self.destination)
+    }
+
+    #[inline(always)]
+    pub fn set_destination(&mut self, addr: Ipv6Addr) {
+        self.destination = addr.octets();
+    }
+
+    #[inline(always)]
+    fn version_class_flow(&self) -> u32 {
+        u32::from_be_bytes(self.version_class_flow)
+    }
+}
+
+/// An owned IPv6 fixed header
+///
+/// Any extension headers are left in the buffer and can be read with `ExtensionHeaders`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ipv6 {
+    pub traffic_class: u8,
+    pub flow_label: u32,
+    /// The length of everything following the fixed header, including extension headers
+    pub payload_len: u16,
+    pub next_header: IpProtocol,
+    pub hop_limit: u8,
+    pub source: Ipv6Addr,
+    pub destination: Ipv6Addr,
+}
+
+impl<B: SplittableBuffer> TypeDecoder<B> for Ipv6 {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (version_class_flow, buffer) = buffer.decode::<u32>()?;
+        if version_class_flow >> 28 != 6 {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "invalid ip version",
+                },
+            ));
+        }
+
+        let (payload_len, buffer) = buffer.decode()?;
+        let (next_header, buffer) = buffer.decode()?;
+        let (hop_limit, buffer) = buffer.decode()?;
+        let (source, buffer) = buffer.decode()?;
+        let (destination, buffer) = buffer.decode()?;
+
+        let value = Self {
+            traffic_class: (version_class_flow >> 20) as u8,
+            flow_label: version_class_flow & FLOW_LABEL_MASK,
+            payload_len,
+            next_header,
+            hop_limit,
+            source,
+            destination,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &Ipv6 {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        if self.flow_label > FLOW_LABEL_MASK {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "flow label out of range",
+                },
+            ));
+        }
+
+        let version_class_flow = (6 << 28) | ((self.traffic_class as u32) << 20) | self.flow_label;
+        let (_, buffer) = buffer.encode((version_class_flow, self.payload_len))?;
+        let (_, buffer) = buffer.encode((self.next_header, self.hop_limit))?;
+        let (_, buffer) = buffer.encode((self.source, self.destination))?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for Ipv6 {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+/// An IPv6 extension header
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExtensionHeader<B> {
+    /// The kind of extension header, as named by the preceding header
+    pub kind: IpProtocol,
+    pub next_header: IpProtocol,
+    /// The header contents following the next header and length fields
+    pub data: B,
+}
+
+impl<B: FiniteBuffer> ExtensionHeader<B> {
+    /// Returns the fragment fields if this is a fragment header
+    #[inline]
+    pub fn fragment(&self) -> Option<Fragment> {
+        if self.kind != IpProtocol::FRAGMENT {
+            return None;
+        }
+        self.data.peek().ok().map(|(fragment, _)| fragment)
+    }
+}
+
+impl<B: EncoderBuffer, S: FiniteBuffer> TypeEncoder<B> for &ExtensionHeader<S> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let data = self.data.as_less_safe_slice();
+
+        let len = if self.kind == IpProtocol::FRAGMENT {
+            if data.len() != FRAGMENT_DATA_LEN {
+                return Err(BufferError::new(
+                    buffer,
+                    BufferErrorReason::InvalidValue {
+                        message: "invalid fragment header length",
+                    },
+                ));
+            }
+            0
+        } else {
+            let total = data.len() + 2;
+            if !total.is_multiple_of(8) || total / 8 > 256 {
+                return Err(BufferError::new(
+                    buffer,
+                    BufferErrorReason::InvalidValue {
+                        message: "invalid extension header length",
+                    },
+                ));
+            }
+            (total / 8 - 1) as u8
+        };
+
+        let (_, buffer) = buffer.encode((self.next_header, len))?;
+        let (_, buffer) = buffer.encode_bytes(data)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, S: FiniteBuffer> TypeEncoder<B> for ExtensionHeader<S> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+const FRAGMENT_DATA_LEN: usize = 6;
+const MORE_FRAGMENTS: u16 = 0x0001;
+
+/// The contents of an IPv6 fragment extension header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Fragment {
+    /// The fragment offset in units of 8 bytes
+    pub offset: u16,
+    pub more_fragments: bool,
+    pub identification: u32,
+}
+
+impl<B: SplittableBuffer> TypeDecoder<B> for Fragment {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (offset_flags, buffer) = buffer.decode::<u16>()?;
+        let (identification, buffer) = buffer.decode()?;
+
+        let value = Self {
+            offset: offset_flags >> 3,
+            more_fragments: offset_flags & MORE_FRAGMENTS != 0,
+            identification,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &Fragment {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let offset_flags = (self.offset << 3) | self.more_fragments as u16;
+        let (_, buffer) = buffer.encode((offset_flags, self.identification))?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for Fragment {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+/// Iterates over the extension headers following an IPv6 header
+///
+/// Iteration stops at the first header that isn't a hop-by-hop, routing, fragment or
+/// destination options header. `next_header` then names the upper-layer protocol and
+/// `into_inner` returns the buffer positioned at its payload.
+#[derive(Clone, Copy, Debug)]
+pub struct ExtensionHeaders<B> {
+    next_header: IpProtocol,
+    buffer: Option<B>,
+}
+
+impl<B> ExtensionHeaders<B> {
+    #[inline(always)]
+    pub fn new(next_header: IpProtocol, buffer: B) -> Self {
+        Self {
+            next_header,
+            buffer: Some(buffer),
+        }
+    }
+
+    /// Returns the protocol of the header following the last extension header read
+    #[inline(always)]
+    pub fn next_header(&self) -> IpProtocol {
+        self.next_header
+    }
+
+    /// Returns the remaining buffer, or `None` if iteration stopped with an error
+    #[inline(always)]
+    pub fn into_inner(self) -> Option<B> {
+        self.buffer
+    }
+}
+
+impl<B: SplittableBuffer> Iterator for ExtensionHeaders<B> {
+    type Item = core::result::Result<ExtensionHeader<B::Slice>, BufferError<B>>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.next_header.is_ipv6_extension() {
+            return None;
+        }
+
+        let buffer = self.buffer.take()?;
+
+        match decode_extension(self.next_header, buffer) {
+            Ok((header, buffer)) => {
+                self.next_header = header.next_header;
+                self.buffer = Some(buffer);
+                Some(Ok(header))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+#[inline(always)]
+fn decode_extension<B: SplittableBuffer>(
+    kind: IpProtocol,
+    buffer: B,
+) -> Result<ExtensionHeader<B::Slice>, B> {
+    let (next_header, buffer) = buffer.decode()?;
+    let (len, buffer) = buffer.decode::<u8>()?;
+
+    // the fragment header has a fixed size and its length field is reserved
+    let data_len = if kind == IpProtocol::FRAGMENT {
+        FRAGMENT_DATA_LEN
+    } else {
+        (len as usize + 1) * 8 - 2
+    };
+    let (data, buffer) = buffer.checked_split(data_len)?;
+
+    let header = ExtensionHeader {
+        kind,
+        next_header,
+        data,
+    };
+    Ok((header, buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zerocopy::{Mut, Ref};
+
+    const PACKET: [u8; 60] = [
+        0x60, 0x12, 0x34, 0x56, // version, traffic class, flow label
+        0x00, 0x14, 0x00, 0x40, // payload length, hop-by-hop, hop limit
+        0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, // source
+        0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, // destination
+        0x2c, 0x00, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00, // hop-by-hop, PadN
+        0x11, 0x00, 0x00, 0x51, 0x00, 0x00, 0x00, 0x2a, // fragment, offset 10, more
+        0xde, 0xad, 0xbe, 0xef, // UDP
+    ];
+
+    #[test]
+    fn header_ref_test() {
+        let buffer = &PACKET[..];
+        let (header, _) = buffer.decode::<Ref<Header, _>>().unwrap();
+        assert_eq!(header.version(), 6);
+        assert_eq!(header.traffic_class(), 0x01);
+        assert_eq!(header.flow_label(), 0x23456);
+        assert_eq!(header.next_header(), IpProtocol::HOP_BY_HOP);
+        assert_eq!(
+            header.destination(),
+            Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1)
+        );
+
+        let mut packet = PACKET;
+        let (mut header, _) = (&mut packet[..]).decode::<Mut<Header, _>>().unwrap();
+        header.set_traffic_class(0xab);
+        assert_eq!(header.flow_label(), 0x23456);
+        assert_eq!(&packet[..4], &[0x6a, 0xb2, 0x34, 0x56]);
+    }
+
+    #[test]
+    fn extension_headers_test() {
+        let (ipv6, buffer) = (&PACKET[..]).decode::<Ipv6>().unwrap();
+        assert_eq!(ipv6.payload_len, 20);
+
+        let mut headers = ExtensionHeaders::new(ipv6.next_header, buffer);
+
+        let hop_by_hop = headers.next().unwrap().unwrap();
+        assert_eq!(hop_by_hop.kind, IpProtocol::HOP_BY_HOP);
+        assert_eq!(hop_by_hop.data, &[0x01, 0x04, 0, 0, 0, 0]);
+        assert!(hop_by_hop.fragment().is_none());
+
+        let fragment = headers.next().unwrap().unwrap();
+        assert_eq!(
+            fragment.fragment(),
+            Some(Fragment {
+                offset: 10,
+                more_fragments: true,
+                identification: 42,
+            })
+        );
+
+        assert!(headers.next().is_none());
+        assert_eq!(headers.next_header(), IpProtocol::UDP);
+        assert_eq!(headers.into_inner(), Some(&[0xde, 0xad, 0xbe, 0xef][..]));
+
+        let mut out = [0u8; 56];
+        let (_, rest) = (&mut out[..]).encode(ipv6).unwrap();
+        let (_, rest) = rest.encode(hop_by_hop).unwrap();
+        rest.encode(fragment).unwrap();
+        assert_eq!(&out[..], &PACKET[..56]);
+    }
+
+    #[test]
+    fn truncated_extension_test() {
+        let mut headers = ExtensionHeaders::new(IpProtocol::ROUTING, &[0x11, 0x01, 0, 0][..]);
+        assert!(headers.next().unwrap().is_err());
+        assert!(headers.next().is_none());
+        assert!(headers.into_inner().is_none());
+    }
+}
@@ -0,0 +1,13 @@
+//! Codecs for common network protocol headers
+//!
+//! Each protocol provides zero-copy header types for use with `zerocopy::Ref` and
+//! `zerocopy::Mut`, along with owned representations that can be encoded directly.
+
+pub mod checksum;
+pub mod ethernet;
+pub mod icmp;
+pub mod ip;
+pub mod ipv4;
+pub mod ipv6;
+pub mod tcp;
+pub mod udp;
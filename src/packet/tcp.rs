@@ -0,0 +1,655 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+    packet::ip::{IpProtocol, Options, PseudoHeader},
+};
+use core::{convert::TryFrom, ops};
+use zerocopy::{AsBytes, FromBytes, Unaligned};
+
+/// The control bits of a TCP header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Flags(pub u8);
+
+impl Flags {
+    pub const FIN: Self = Self(0x01);
+    pub const SYN: Self = Self(0x02);
+    pub const RST: Self = Self(0x04);
+    pub const PSH: Self = Self(0x08);
+    pub const ACK: Self = Self(0x10);
+    pub const URG: Self = Self(0x20);
+    pub const ECE: Self = Self(0x40);
+    pub const CWR: Self = Self(0x80);
+
+    /// Returns `true` if all of the bits in `other` are set
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for Flags {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A zero-copy TCP header, not including any options
+///
+/// The options, if any, immediately follow the header and are `options_len` bytes long.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct Header {
+    source_port: [u8; 2],
+    destination_port: [u8; 2],
+    sequence: [u8; 4],
+    acknowledgment: [u8; 4],
+    data_offset: u8,
+    flags: u8,
+    window: [u8; 2],
+    checksum: [u8; 2],
+    urgent_pointer: [u8; 2],
+}
+
+const CHECKSUM_OFFSET: usize = 16;
+
+impl Header {
+    pub const LEN: usize = 20;
+
+    #[inline(always)]
+    pub fn source_port(&self) -> u16 {
+        u16::from_be_bytes(self.source_port)
+    }
+
+    #[inline(always)]
+    pub fn set_source_port(&mut self, port: u16) {
+        self.source_port = port.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn destination_port(&self) -> u16 {
+        u16::from_be_bytes(self.destination_port)
+    }
+
+    #[inline(always)]
+    pub fn set_destination_port(&mut self, port: u16) {
+        self.destination_port = port.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn sequence(&self) -> u32 {
+        u32::from_be_bytes(self.sequence)
+    }
+
+    #[inline(always)]
+    pub fn set_sequence(&mut self, sequence: u32) {
+        self.sequence = sequence.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn acknowledgment(&self) -> u32 {
+        u32::from_be_bytes(self.acknowledgment)
+    }
+
+    #[inline(always)]
+    pub fn set_acknowledgment(&mut self, acknowledgment: u32) {
+        self.acknowledgment = acknowledgment.to_be_bytes();
+    }
+
+    /// Returns the header length in 32-bit words
+    #[inline(always)]
+    pub fn data_offset(&self) -> u8 {
+        self.data_offset >> 4
+    }
+
+    #[inline(always)]
+    pub fn set_data_offset(&mut self, data_offset: u8) {
+        self.data_offset = (data_offset << 4) | (self.data_offset & 0x0f);
+    }
+
+    /// Returns the header length in bytes, including options
+    #[inline(always)]
+    pub fn header_len(&self) -> usize {
+        self.data_offset() as usize * 4
+    }
+
+    /// Returns the length of the options following the header
+    ///
+    /// A malformed header with a data offset smaller than 5 has no options.
+    #[inline(always)]
+    pub fn options_len(&self) -> usize {
+        self.header_len().saturating_sub(Self::LEN)
+    }
+
+    #[inline(always)]
+    pub fn flags(&self) -> Flags {
+        Flags(self.flags)
+    }
+
+    #[inline(always)]
+    pub fn set_flags(&mut self, flags: Flags) {
+        self.flags = flags.0;
+    }
+
+    #[inline(always)]
+    pub fn window(&self) -> u16 {
+        u16::from_be_bytes(self.window)
+    }
+
+    #[inline(always)]
+    pub fn set_window(&mut self, window: u16) {
+        self.window = window.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(self.checksum)
+    }
+
+    #[inline(always)]
+    pub fn set_checksum(&mut self, checksum: u16) {
+        self.checksum = checksum.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn urgent_pointer(&self) -> u16 {
+        u16::from_be_bytes(self.urgent_pointer)
+    }
+
+    #[inline(always)]
+    pub fn set_urgent_pointer(&mut self, urgent_pointer: u16) {
+        self.urgent_pointer = urgent_pointer.to_be_bytes();
+    }
+
+    /// Computes the checksum over the pseudo-header, header, `options` and `payload`,
+    /// ignoring the stored value
+    #[inline]
+    pub fn compute_checksum(&self, pseudo: &PseudoHeader, options: &[u8], payload: &[u8]) -> u16 {
+        let len = Self::LEN + options.len() + payload.len();
+        pseudo
+            .checksum(IpProtocol::TCP, len)
+            .add_header(self.as_bytes(), CHECKSUM_OFFSET)
+            .add_bytes(options)
+            .add_bytes(payload)
+            .finish()
+    }
+
+    /// Stores the checksum computed over the pseudo-header, header, `options` and `payload`
+    #[inline(always)]
+    pub fn fill_checksum(&mut self, pseudo: &PseudoHeader, options: &[u8], payload: &[u8]) {
+        let checksum = self.compute_checksum(pseudo, options, payload);
+        self.set_checksum(checksum);
+    }
+}
+
+/// An owned TCP segment
+///
+/// The segment extends to the end of the buffer, so decoding should be limited to the
+/// IP payload length. Decoding with a `PseudoHeader` verifies the checksum and encoding
+/// with one computes it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Tcp<P> {
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub sequence: u32,
+    pub acknowledgment: u32,
+    pub flags: Flags,
+    pub window: u16,
+    pub urgent_pointer: u16,
+    pub options: Options,
+    pub payload: P,
+}
+
+impl<P> Tcp<P> {
+    /// Returns the header length in bytes, including options
+    #[inline(always)]
+    pub fn header_len(&self) -> usize {
+        Header::LEN + self.options.len()
+    }
+
+    /// Returns an iterator over the header options
+    #[inline(always)]
+    pub fn options(&self) -> TcpOptions<&[u8]> {
+        TcpOptions::new(self.options.as_bytes())
+    }
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<P: FiniteBuffer> Tcp<P> {
+    /// Returns the length of the segment, including the header
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.header_len() + self.payload.len()
+    }
+
+    /// Returns `true` if the payload is empty
+    #[inline(always)]
+    pub fn payload_is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+}
+
+#[inline(always)]
+fn decode_parts<B: FiniteBuffer>(buffer: B) -> Result<(Header, Options, B), B> {
+    let (fixed, buffer) = buffer.checked_split(Header::LEN)?;
+    let mut header = Header::default();
+    header
+        .as_bytes_mut()
+        .copy_from_slice(fixed.as_less_safe_slice());
+
+    if header.header_len() < Header::LEN {
+        return Err(BufferError::new(
+            buffer,
+            BufferErrorReason::InvalidValue {
+                message: "invalid data offset",
+            },
+        ));
+    }
+
+    let (options, buffer) = buffer.checked_split(header.options_len())?;
+    let options = match Options::new(options.as_less_safe_slice()) {
+        Some(options) => options,
+        None => unreachable!("the data offset limits the options to 40 bytes in 32-bit words"),
+    };
+
+    let (payload, buffer) = buffer.consume();
+    Ok(((header, options, payload), buffer))
+}
+
+#[inline(always)]
+fn from_parts<P>(header: Header, options: Options, payload: P) -> Tcp<P> {
+    Tcp {
+        source_port: header.source_port(),
+        destination_port: header.destination_port(),
+        sequence: header.sequence(),
+        acknowledgment: header.acknowledgment(),
+        flags: header.flags(),
+        window: header.window(),
+        urgent_pointer: header.urgent_pointer(),
+        options,
+        payload,
+    }
+}
+
+/// Decodes the segment without verifying the checksum
+impl<B: FiniteBuffer> TypeDecoder<B> for Tcp<B> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let ((header, options, payload), buffer) = decode_parts(buffer)?;
+        Ok((from_parts(header, options, payload), buffer))
+    }
+}
+
+impl<B: FiniteBuffer> Decoder<Tcp<B>, B> for PseudoHeader {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Tcp<B>, B> {
+        let ((header, options, payload), buffer) = decode_parts(buffer)?;
+
+        let expected =
+            header.compute_checksum(&self, options.as_bytes(), payload.as_less_safe_slice());
+        let actual = header.checksum();
+        if expected != actual {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::ChecksumMismatch {
+                    expected: expected as u64,
+                    actual: actual as u64,
+                },
+            ));
+        }
+
+        Ok((from_parts(header, options, payload), buffer))
+    }
+}
+
+impl<'a, B: EncoderBuffer, P: FiniteBuffer> Encoder<&'a Tcp<P>, B> for PseudoHeader {
+    #[inline(always)]
+    fn encode_into(self, tcp: &'a Tcp<P>, buffer: B) -> Result<(), B> {
+        if u16::try_from(tcp.len()).is_err() {
+            return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow));
+        }
+        let options = tcp.options.as_bytes();
+        let payload = tcp.payload.as_less_safe_slice();
+
+        let mut header = Header::default();
+        header.set_source_port(tcp.source_port);
+        header.set_destination_port(tcp.destination_port);
+        header.set_sequence(tcp.sequence);
+        header.set_acknowledgment(tcp.acknowledgment);
+        header.set_data_offset((tcp.header_len() / 4) as u8);
+        header.set_flags(tcp.flags);
+        header.set_window(tcp.window);
+        header.set_urgent_pointer(tcp.urgent_pointer);
+        header.fill_checksum(&self, options, payload);
+
+        let (_, buffer) = buffer.encode_bytes(header.as_bytes())?;
+        let (_, buffer) = buffer.encode_bytes(options)?;
+        let (_, buffer) = buffer.encode_bytes(payload)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, P: FiniteBuffer> Encoder<Tcp<P>, B> for PseudoHeader {
+    #[inline(always)]
+    fn encode_into(self, tcp: Tcp<P>, buffer: B) -> Result<(), B> {
+        self.encode_into(&tcp, buffer)
+    }
+}
+
+const END_OF_LIST: u8 = 0;
+const NO_OPERATION: u8 = 1;
+const MAX_SEGMENT_SIZE: u8 = 2;
+const WINDOW_SCALE: u8 = 3;
+const SACK_PERMITTED: u8 = 4;
+const SACK: u8 = 5;
+const TIMESTAMPS: u8 = 8;
+
+const INVALID_OPTION_LEN: BufferErrorReason = BufferErrorReason::InvalidValue {
+    message: "invalid option length",
+};
+
+/// A TCP header option
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TcpOption<B> {
+    MaxSegmentSize(u16),
+    WindowScale(u8),
+    SackPermitted,
+    /// The left and right edges of each acknowledged block, as pairs of `u32`s
+    Sack(B),
+    Timestamps {
+        value: u32,
+        echo_reply: u32,
+    },
+    Unknown {
+        kind: u8,
+        data: B,
+    },
+}
+
+impl<B: EncoderBuffer, S: FiniteBuffer> TypeEncoder<B> for &TcpOption<S> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (kind, data) = match self {
+            TcpOption::MaxSegmentSize(mss) => {
+                let (_, buffer) = buffer.encode((MAX_SEGMENT_SIZE, 4u8, *mss))?;
+                return Ok(((), buffer));
+            }
+            TcpOption::WindowScale(shift) => {
+                let (_, buffer) = buffer.encode((WINDOW_SCALE, 3u8, *shift))?;
+                return Ok(((), buffer));
+            }
+            TcpOption::SackPermitted => {
+                let (_, buffer) = buffer.encode((SACK_PERMITTED, 2u8))?;
+                return Ok(((), buffer));
+            }
+            TcpOption::Timestamps { value, echo_reply } => {
+                let (_, buffer) = buffer.encode((TIMESTAMPS, 10u8, *value, *echo_reply))?;
+                return Ok(((), buffer));
+            }
+            TcpOption::Sack(data) => (SACK, data.as_less_safe_slice()),
+            TcpOption::Unknown { kind, data } => (*kind, data.as_less_safe_slice()),
+        };
+
+        let len = match u8::try_from(data.len() + 2) {
+            Ok(len) => len,
+            Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+        let (_, buffer) = buffer.encode((kind, len))?;
+        let (_, buffer) = buffer.encode_bytes(data)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, S: FiniteBuffer> TypeEncoder<B> for TcpOption<S> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self).encode_type(buffer)
+    }
+}
+
+/// Iterates over the options of a TCP header
+///
+/// No-operation padding is skipped and iteration stops at the end of option list marker.
+#[derive(Clone, Copy, Debug)]
+pub struct TcpOptions<B> {
+    buffer: Option<B>,
+}
+
+impl<B> TcpOptions<B> {
+    #[inline(always)]
+    pub fn new(buffer: B) -> Self {
+        Self {
+            buffer: Some(buffer),
+        }
+    }
+}
+
+impl<B: FiniteBuffer> Iterator for TcpOptions<B> {
+    type Item = core::result::Result<TcpOption<B>, BufferError<B>>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = self.buffer.take()?;
+
+        match decode_option(buffer) {
+            Ok((Some(option), buffer)) => {
+                self.buffer = Some(buffer);
+                Some(Ok(option))
+            }
+            Ok((None, _)) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+#[inline(always)]
+fn decode_option<B: FiniteBuffer>(mut buffer: B) -> Result<Option<TcpOption<B>>, B> {
+    let kind = loop {
+        if buffer.is_empty() {
+            return Ok((None, buffer));
+        }
+
+        let (kind, next) = buffer.decode::<u8>()?;
+        buffer = next;
+        match kind {
+            END_OF_LIST => return Ok((None, buffer)),
+            NO_OPERATION => continue,
+            kind => break kind,
+        }
+    };
+
+    let (len, buffer) = buffer.decode::<u8>()?;
+    let data_len = match (len as usize).checked_sub(2) {
+        Some(len) => len,
+        None => return Err(BufferError::new(buffer, INVALID_OPTION_LEN)),
+    };
+
+    let valid = match kind {
+        MAX_SEGMENT_SIZE => data_len == 2,
+        WINDOW_SCALE => data_len == 1,
+        SACK_PERMITTED => data_len == 0,
+        SACK => data_len % 8 == 0,
+        TIMESTAMPS => data_len == 8,
+        _ => true,
+    };
+    if !valid {
+        return Err(BufferError::new(buffer, INVALID_OPTION_LEN));
+    }
+
+    let (data, buffer) = buffer.checked_split(data_len)?;
+
+    let option = match kind {
+        MAX_SEGMENT_SIZE => {
+            let (mss, buffer) = map_buffer_error!(data.peek(), buffer);
+            return Ok((Some(TcpOption::MaxSegmentSize(mss)), buffer));
+        }
+        WINDOW_SCALE => {
+            let (shift, buffer) = map_buffer_error!(data.peek(), buffer);
+            return Ok((Some(TcpOption::WindowScale(shift)), buffer));
+        }
+        SACK_PERMITTED => TcpOption::SackPermitted,
+        SACK => TcpOption::Sack(data),
+        TIMESTAMPS => {
+            let ((value, echo_reply), buffer) = map_buffer_error!(data.peek(), buffer);
+            return Ok((Some(TcpOption::Timestamps { value, echo_reply }), buffer));
+        }
+        kind => TcpOption::Unknown { kind, data },
+    };
+
+    Ok((Some(option), buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        packet::ipv6::Ipv6,
+        zerocopy::{Mut, Ref},
+    };
+    use core::net::Ipv6Addr;
+
+    const PSEUDO: PseudoHeader = PseudoHeader::Ipv6 {
+        source: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+        destination: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2),
+    };
+
+    const OPTIONS: [u8; 20] = [
+        0x02, 0x04, 0x05, 0xb4, // MSS 1460
+        0x04, 0x02, // SACK permitted
+        0x08, 0x0a, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // timestamps
+        0x01, // no-op
+        0x03, 0x03, 0x07, // window scale
+    ];
+
+    fn syn() -> Tcp<&'static [u8]> {
+        Tcp {
+            source_port: 49152,
+            destination_port: 443,
+            sequence: 1000,
+            acknowledgment: 0,
+            flags: Flags::SYN,
+            window: 65535,
+            urgent_pointer: 0,
+            options: Options::new(&OPTIONS).unwrap(),
+            payload: &[],
+        }
+    }
+
+    #[test]
+    fn options_test() {
+        let tcp = syn();
+        let mut options = tcp.options();
+        assert_eq!(
+            options.next().unwrap().unwrap(),
+            TcpOption::MaxSegmentSize(1460)
+        );
+        assert_eq!(options.next().unwrap().unwrap(), TcpOption::SackPermitted);
+        assert_eq!(
+            options.next().unwrap().unwrap(),
+            TcpOption::Timestamps {
+                value: 1,
+                echo_reply: 0
+            }
+        );
+        assert_eq!(options.next().unwrap().unwrap(), TcpOption::WindowScale(7));
+        assert!(options.next().is_none());
+
+        let mut sack = TcpOptions::new(&[1, 1, 5, 10, 0, 0, 0, 1, 0, 0, 0, 9, 0, 0][..]);
+        let block = sack.next().unwrap().unwrap();
+        assert_eq!(block, TcpOption::Sack(&[0, 0, 0, 1, 0, 0, 0, 9][..]));
+        assert!(sack.next().is_none());
+
+        let mut out = [0u8; 10];
+        (&mut out[..]).encode(block).unwrap();
+        assert_eq!(&out, &[5, 10, 0, 0, 0, 1, 0, 0, 0, 9]);
+
+        let mut invalid = TcpOptions::new(&[2, 3, 5][..]);
+        assert!(invalid.next().unwrap().is_err());
+        assert!(invalid.next().is_none());
+    }
+
+    #[test]
+    fn encode_options_test() {
+        let mut out = [0u8; Options::MAX_LEN];
+        let (_, buffer) = (&mut out[..])
+            .encode(TcpOption::<&[u8]>::MaxSegmentSize(1460))
+            .unwrap();
+        let (_, buffer) = buffer.encode(TcpOption::<&[u8]>::SackPermitted).unwrap();
+        let (_, buffer) = buffer
+            .encode(TcpOption::<&[u8]>::Timestamps {
+                value: 1,
+                echo_reply: 0,
+            })
+            .unwrap();
+        let (_, buffer) = buffer.encode(NO_OPERATION).unwrap();
+        let (_, buffer) = buffer.encode(TcpOption::<&[u8]>::WindowScale(7)).unwrap();
+        let len = Options::MAX_LEN - buffer.len();
+
+        let options = Options::new_padded(&out[..len]).unwrap();
+        assert_eq!(options.as_bytes(), &OPTIONS[..]);
+    }
+
+    #[test]
+    fn header_ref_test() {
+        let mut out = [0u8; 40];
+        (&mut out[..]).encode_with(syn(), PSEUDO).unwrap();
+
+        let (header, buffer) = (&out[..]).decode::<Ref<Header, _>>().unwrap();
+        assert_eq!(header.data_offset(), 10);
+        assert_eq!(header.options_len(), 20);
+        assert!(header.flags().contains(Flags::SYN));
+        assert!(!header.flags().contains(Flags::SYN | Flags::ACK));
+        assert_eq!(
+            header.compute_checksum(&PSEUDO, buffer, &[]),
+            header.checksum()
+        );
+
+        let (mut header, buffer) = (&mut out[..]).decode::<Mut<Header, _>>().unwrap();
+        header.set_flags(Flags::SYN | Flags::ACK);
+        header.fill_checksum(&PSEUDO, buffer, &[]);
+        let (tcp, _): (Tcp<_>, _) = (&out[..]).decode_with(PSEUDO).unwrap();
+        assert_eq!(tcp.flags, Flags::SYN | Flags::ACK);
+        assert_eq!(tcp.payload, &[]);
+    }
+
+    #[test]
+    fn packet_test() {
+        let tcp = Tcp {
+            flags: Flags::ACK | Flags::PSH,
+            payload: &b"hello"[..],
+            ..syn()
+        };
+        let ipv6 = Ipv6 {
+            traffic_class: 0,
+            flow_label: 0,
+            payload_len: tcp.len() as u16,
+            next_header: IpProtocol::TCP,
+            hop_limit: 64,
+            source: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+            destination: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2),
+        };
+
+        let mut out = [0u8; 85];
+        let (_, buffer) = (&mut out[..]).encode(ipv6).unwrap();
+        buffer.encode_with(&tcp, PseudoHeader::from(&ipv6)).unwrap();
+
+        let (ipv6, buffer) = (&out[..]).decode::<Ipv6>().unwrap();
+        assert_eq!(buffer.len(), ipv6.payload_len as usize);
+        let (actual, _): (Tcp<_>, _) = buffer.decode_with(PseudoHeader::from(&ipv6)).unwrap();
+        assert_eq!(actual, tcp);
+
+        let mut corrupt = out;
+        corrupt[84] ^= 1;
+        let err = (&corrupt[40..])
+            .decode_with(PSEUDO)
+            .map(|(tcp, _): (Tcp<&[u8]>, _)| tcp)
+            .unwrap_err();
+        assert!(matches!(
+            err.reason,
+            BufferErrorReason::ChecksumMismatch { .. }
+        ));
+    }
+}
@@ -0,0 +1,318 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
+    decode::{Decoder, TypeDecoder},
+    encode::{Encoder, EncoderBuffer},
+    packet::ip::{IpProtocol, PseudoHeader},
+};
+use core::convert::TryFrom;
+use zerocopy::{AsBytes, FromBytes, Unaligned};
+
+/// A zero-copy UDP header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, FromBytes, AsBytes, Unaligned)]
+#[repr(C)]
+pub struct Header {
+    source_port: [u8; 2],
+    destination_port: [u8; 2],
+    len: [u8; 2],
+    checksum: [u8; 2],
+}
+
+const CHECKSUM_OFFSET: usize = 6;
+
+impl Header {
+    pub const LEN: usize = 8;
+
+    #[inline(always)]
+    pub fn source_port(&self) -> u16 {
+        u16::from_be_bytes(self.source_port)
+    }
+
+    #[inline(always)]
+    pub fn set_source_port(&mut self, port: u16) {
+        self.source_port = port.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn destination_port(&self) -> u16 {
+        u16::from_be_bytes(self.destination_port)
+    }
+
+    #[inline(always)]
+    pub fn set_destination_port(&mut self, port: u16) {
+        self.destination_port = port.to_be_bytes();
+    }
+
+    /// Returns the length of the datagram, including the header
+    #[inline(always)]
+    pub fn total_len(&self) -> u16 {
+        u16::from_be_bytes(self.len)
+    }
+
+    #[inline(always)]
+    pub fn set_total_len(&mut self, len: u16) {
+        self.len = len.to_be_bytes();
+    }
+
+    #[inline(always)]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes(self.checksum)
+    }
+
+    #[inline(always)]
+    pub fn set_checksum(&mut self, checksum: u16) {
+        self.checksum = checksum.to_be_bytes();
+    }
+
+    /// Computes the checksum over the pseudo-header, header and `payload`, ignoring the
+    /// stored value
+    ///
+    /// A computed value of zero is returned as `0xffff`, since zero means no checksum.
+    #[inline]
+    pub fn compute_checksum(&self, pseudo: &PseudoHeader, payload: &[u8]) -> u16 {
+        let checksum = pseudo
+            .checksum(IpProtocol::UDP, Self::LEN + payload.len())
+            .add_header(self.as_bytes(), CHECKSUM_OFFSET)
+            .add_bytes(payload)
+            .finish();
+
+        if checksum == 0 {
+            0xffff
+        } else {
+            checksum
+        }
+    }
+
+    /// Stores the checksum computed over the pseudo-header, header and `payload`
+    #[inline(always)]
+    pub fn fill_checksum(&mut self, pseudo: &PseudoHeader, payload: &[u8]) {
+        let checksum = self.compute_checksum(pseudo, payload);
+        self.set_checksum(checksum);
+    }
+}
+
+/// An owned UDP datagram
+///
+/// The length and checksum are derived from the payload. Decoding with a `PseudoHeader`
+/// verifies the checksum and encoding with one computes it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Udp<P> {
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub payload: P,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<P: FiniteBuffer> Udp<P> {
+    /// Returns the length of the datagram, including the header
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        Header::LEN + self.payload.len()
+    }
+
+    /// Returns `true` if the payload is empty
+    #[inline(always)]
+    pub fn payload_is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+}
+
+#[inline(always)]
+fn decode_parts<B: SplittableBuffer>(buffer: B) -> Result<(Header, B::Slice), B> {
+    let (fixed, buffer) = buffer.checked_split(Header::LEN)?;
+    let mut header = Header::default();
+    header
+        .as_bytes_mut()
+        .copy_from_slice(fixed.as_less_safe_slice());
+
+    let payload_len = match (header.total_len() as usize).checked_sub(Header::LEN) {
+        Some(len) => len,
+        None => {
+            return Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "length is shorter than the header",
+                },
+            ))
+        }
+    };
+
+    let (payload, buffer) = buffer.checked_split(payload_len)?;
+    Ok(((header, payload), buffer))
+}
+
+/// Decodes the datagram without verifying the checksum
+impl<B: SplittableBuffer> TypeDecoder<B> for Udp<B::Slice> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let ((header, payload), buffer) = decode_parts(buffer)?;
+        let value = Self {
+            source_port: header.source_port(),
+            destination_port: header.destination_port(),
+            payload,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<B: SplittableBuffer> Decoder<Udp<B::Slice>, B> for PseudoHeader {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<Udp<B::Slice>, B> {
+        let ((header, payload), buffer) = decode_parts(buffer)?;
+
+        let actual = header.checksum();
+        // a zero checksum means the sender didn't compute one, which IPv6 doesn't allow
+        if actual != 0 || matches!(self, Self::Ipv6 { .. }) {
+            let expected = header.compute_checksum(&self, payload.as_less_safe_slice());
+            if expected != actual {
+                return Err(BufferError::new(
+                    buffer,
+                    BufferErrorReason::ChecksumMismatch {
+                        expected: expected as u64,
+                        actual: actual as u64,
+                    },
+                ));
+            }
+        }
+
+        let value = Udp {
+            source_port: header.source_port(),
+            destination_port: header.destination_port(),
+            payload,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<'a, B: EncoderBuffer, P: FiniteBuffer> Encoder<&'a Udp<P>, B> for PseudoHeader {
+    #[inline(always)]
+    fn encode_into(self, udp: &'a Udp<P>, buffer: B) -> Result<(), B> {
+        let len = match u16::try_from(udp.len()) {
+            Ok(len) => len,
+            Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+        let payload = udp.payload.as_less_safe_slice();
+
+        let mut header = Header::default();
+        header.set_source_port(udp.source_port);
+        header.set_destination_port(udp.destination_port);
+        header.set_total_len(len);
+        header.fill_checksum(&self, payload);
+
+        let (_, buffer) = buffer.encode_bytes(header.as_bytes())?;
+        let (_, buffer) = buffer.encode_bytes(payload)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, P: FiniteBuffer> Encoder<Udp<P>, B> for PseudoHeader {
+    #[inline(always)]
+    fn encode_into(self, udp: Udp<P>, buffer: B) -> Result<(), B> {
+        self.encode_into(&udp, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode::DecoderBuffer,
+        packet::ipv4::Ipv4,
+        zerocopy::{Mut, Ref},
+    };
+    use core::net::{Ipv4Addr, Ipv6Addr};
+
+    const PSEUDO: PseudoHeader = PseudoHeader::Ipv4 {
+        source: Ipv4Addr::new(10, 0, 0, 1),
+        destination: Ipv4Addr::new(10, 0, 0, 2),
+    };
+
+    const DATAGRAM: [u8; 12] = [
+        0x30, 0x39, 0x00, 0x35, // ports 12345 and 53
+        0x00, 0x0c, 0x1d, 0xc8, // length, checksum
+        0xde, 0xad, 0xbe, 0xef, // payload
+    ];
+
+    #[test]
+    fn header_ref_test() {
+        let (header, payload) = (&DATAGRAM[..]).decode::<Ref<Header, _>>().unwrap();
+        assert_eq!(header.source_port(), 12345);
+        assert_eq!(header.destination_port(), 53);
+        assert_eq!(header.total_len(), 12);
+        assert_eq!(header.compute_checksum(&PSEUDO, payload), header.checksum());
+
+        let mut datagram = DATAGRAM;
+        let (mut header, payload) = (&mut datagram[..]).decode::<Mut<Header, _>>().unwrap();
+        header.set_destination_port(54);
+        header.fill_checksum(&PSEUDO, payload);
+        assert_eq!(&datagram[6..8], &[0x1d, 0xc7]);
+    }
+
+    #[test]
+    fn checksum_test() {
+        let (udp, _): (Udp<_>, _) = (&DATAGRAM[..]).decode_with(PSEUDO).unwrap();
+        assert_eq!(udp.payload, &[0xde, 0xad, 0xbe, 0xef]);
+
+        let mut out = [0u8; 12];
+        (&mut out[..]).encode_with(udp, PSEUDO).unwrap();
+        assert_eq!(out, DATAGRAM);
+
+        let other = PseudoHeader::Ipv4 {
+            source: Ipv4Addr::new(10, 0, 0, 3),
+            destination: Ipv4Addr::new(10, 0, 0, 2),
+        };
+        let err = (&DATAGRAM[..])
+            .decode_with(other)
+            .map(|(udp, _): (Udp<&[u8]>, _)| udp)
+            .unwrap_err();
+        assert!(matches!(
+            err.reason,
+            BufferErrorReason::ChecksumMismatch { .. }
+        ));
+
+        // a zero checksum is only skipped over IPv4
+        let mut unchecked = DATAGRAM;
+        unchecked[6..8].copy_from_slice(&[0, 0]);
+        let (_, _): (Udp<&[u8]>, _) = (&unchecked[..]).decode_with(other).unwrap();
+        let ipv6 = PseudoHeader::Ipv6 {
+            source: Ipv6Addr::LOCALHOST,
+            destination: Ipv6Addr::LOCALHOST,
+        };
+        assert!((&unchecked[..])
+            .decode_with(ipv6)
+            .map(|(udp, _): (Udp<&[u8]>, _)| udp)
+            .is_err());
+    }
+
+    #[test]
+    fn packet_test() {
+        let udp = Udp {
+            source_port: 12345,
+            destination_port: 53,
+            payload: &[0xde, 0xad, 0xbe, 0xef][..],
+        };
+        let ipv4 = Ipv4 {
+            dscp: 0,
+            ecn: 0,
+            identification: 1,
+            dont_fragment: true,
+            more_fragments: false,
+            fragment_offset: 0,
+            ttl: 64,
+            protocol: IpProtocol::UDP,
+            source: Ipv4Addr::new(10, 0, 0, 1),
+            destination: Ipv4Addr::new(10, 0, 0, 2),
+            options: Default::default(),
+            payload_len: udp.len() as u16,
+        };
+
+        let mut out = [0u8; 32];
+        let (_, buffer) = (&mut out[..]).encode(ipv4).unwrap();
+        buffer.encode_with(&udp, PseudoHeader::from(&ipv4)).unwrap();
+        assert_eq!(&out[20..], &DATAGRAM[..]);
+
+        let (ipv4, buffer) = (&out[..]).decode::<Ipv4>().unwrap();
+        let (actual, buffer): (Udp<_>, _) = buffer.decode_with(PseudoHeader::from(&ipv4)).unwrap();
+        assert_eq!(actual, udp);
+        assert!(buffer.is_empty());
+    }
+}
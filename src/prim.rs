@@ -1,10 +1,10 @@
 use crate::{
-    buffer::{FiniteBuffer, Result, SplittableBuffer},
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result, SplittableBuffer},
     decode::{Decoder, DecoderBuffer, TypeDecoder},
     encode::{Encoder, EncoderBuffer, TypeEncoder},
     endian::{Big, Little, NETWORK},
 };
-use core::convert::TryInto;
+use core::{convert::TryInto, marker::PhantomData};
 
 macro_rules! impl_int_tests {
     ($ty:ident, $tests:ident) => {
@@ -355,3 +355,78 @@ where
         }
     }
 }
+
+impl<B: SplittableBuffer, T> TypeDecoder<B> for PhantomData<T> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        Ok((PhantomData, buffer))
+    }
+}
+
+impl<B: EncoderBuffer, T> TypeEncoder<B> for PhantomData<T> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, T> TypeEncoder<B> for &PhantomData<T> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        Ok(((), buffer))
+    }
+}
+
+/// `Result` values are preceded by a `u8` discriminant of `0` for `Ok` and `1` for `Err`
+impl<B: SplittableBuffer, T: TypeDecoder<B>, E: TypeDecoder<B>> TypeDecoder<B>
+    for core::result::Result<T, E>
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (tag, buffer) = buffer.decode::<u8>()?;
+        match tag {
+            0 => {
+                let (value, buffer) = buffer.decode()?;
+                Ok((Ok(value), buffer))
+            }
+            1 => {
+                let (value, buffer) = buffer.decode()?;
+                Ok((Err(value), buffer))
+            }
+            tag => Err(BufferError::new(
+                buffer,
+                BufferErrorReason::UnknownTag { tag: tag as u128 },
+            )),
+        }
+    }
+}
+
+macro_rules! impl_result_encoder {
+    ($($ref:tt)*) => {
+        impl<'a, B: EncoderBuffer, T, E> TypeEncoder<B> for $($ref)* core::result::Result<T, E>
+        where
+            $($ref)* T: TypeEncoder<B>,
+            $($ref)* E: TypeEncoder<B>,
+        {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                match self {
+                    Ok(value) => {
+                        let (_, buffer) = buffer.encode(0u8)?;
+                        let (_, buffer) = buffer.encode(value)?;
+                        Ok(((), buffer))
+                    }
+                    Err(value) => {
+                        let (_, buffer) = buffer.encode(1u8)?;
+                        let (_, buffer) = buffer.encode(value)?;
+                        Ok(((), buffer))
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_result_encoder!();
+impl_result_encoder!(&'a);
+impl_result_encoder!(&'a mut);
@@ -0,0 +1,171 @@
+use crate::{
+    buffer::{BorrowedBuffer, Result, SplittableBuffer},
+    decode::{DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, TypeEncoder},
+};
+use alloc::{borrow::Cow, boxed::Box, rc::Rc};
+use core::mem::size_of;
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+macro_rules! impl_ptr {
+    ($ty:ident) => {
+        impl<B: SplittableBuffer, T: TypeDecoder<B>> TypeDecoder<B> for $ty<T> {
+            #[inline(always)]
+            fn decode_type(buffer: B) -> Result<Self, B> {
+                buffer.decode_nested(|buffer| {
+                    let ((), buffer) = buffer.reserve_alloc(size_of::<T>())?;
+                    let (value, buffer) = buffer.decode()?;
+                    Ok(($ty::new(value), buffer))
+                })
+            }
+        }
+
+        impl<B: EncoderBuffer, T> TypeEncoder<B> for &$ty<T>
+        where
+            for<'a> &'a T: TypeEncoder<B>,
+        {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> Result<(), B> {
+                (&**self).encode_type(buffer)
+            }
+        }
+    };
+}
+
+impl_ptr!(Box);
+impl_ptr!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+impl_ptr!(Arc);
+
+impl<B: EncoderBuffer, T: TypeEncoder<B>> TypeEncoder<B> for Box<T> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (*self).encode_type(buffer)
+    }
+}
+
+impl<B: EncoderBuffer, T> TypeEncoder<B> for Rc<T>
+where
+    for<'a> &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&*self).encode_type(buffer)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<B: EncoderBuffer, T> TypeEncoder<B> for Arc<T>
+where
+    for<'a> &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&*self).encode_type(buffer)
+    }
+}
+
+/// Borrows all of the remaining bytes without copying
+impl<'a, B: BorrowedBuffer<'a>> TypeDecoder<B> for Cow<'a, [u8]> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (slice, buffer) = buffer.consume();
+        Ok((Cow::Borrowed(slice.into_less_safe_slice()), buffer))
+    }
+}
+
+impl<'a, B: EncoderBuffer> TypeEncoder<B> for Cow<'a, [u8]> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_bytes(self)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, B: EncoderBuffer> TypeEncoder<B> for &Cow<'a, [u8]> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_bytes(self)?;
+        Ok(((), buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        buffer::BufferErrorReason,
+        len::LenPrefix,
+        limit::{Limited, Limits},
+    };
+
+    #[test]
+    fn box_test() {
+        let (value, _) = (&[0u8, 7][..]).decode::<Box<u16>>().unwrap();
+        assert_eq!(*value, 7);
+
+        let mut out = [0u8; 2];
+        (&mut out[..]).encode(value).unwrap();
+        assert_eq!(out, [0, 7]);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct List(Option<Box<List>>);
+
+    impl<B: SplittableBuffer> TypeDecoder<B> for List {
+        fn decode_type(buffer: B) -> Result<Self, B> {
+            let (more, buffer) = buffer.decode::<u8>()?;
+            if more == 0 {
+                return Ok((Self(None), buffer));
+            }
+            let (next, buffer) = buffer.decode()?;
+            Ok((Self(Some(next)), buffer))
+        }
+    }
+
+    #[test]
+    fn recursive_test() {
+        let limits = Limits::new().with_max_depth(2);
+
+        let (value, _) = Limited::new(&[1u8, 1, 0][..], &limits)
+            .decode::<List>()
+            .unwrap();
+        assert_eq!(
+            value,
+            List(Some(Box::new(List(Some(Box::new(List(None)))))))
+        );
+
+        let err = Limited::new(&[1u8, 1, 1, 0][..], &limits)
+            .decode::<List>()
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 2 });
+        assert_eq!(limits.depth(), 0);
+    }
+
+    #[cfg(target_has_atomic = "ptr")]
+    #[test]
+    fn shared_test() {
+        let (value, _) = (&[0u8, 7][..]).decode::<Rc<u16>>().unwrap();
+        let (other, _) = (&[0u8, 7][..]).decode::<Arc<u16>>().unwrap();
+        assert_eq!(*value, *other);
+
+        let mut out = [0u8; 4];
+        let (_, rest) = (&mut out[..]).encode(value).unwrap();
+        rest.encode(&other).unwrap();
+        assert_eq!(out, [0, 7, 0, 7]);
+    }
+
+    #[test]
+    fn cow_test() {
+        let buffer = &[2u8, 1, 2, 3][..];
+        let (value, buffer): (Cow<[u8]>, _) = buffer.decode_with(LenPrefix::new::<u8>()).unwrap();
+        assert!(matches!(value, Cow::Borrowed(&[1, 2])));
+        assert_eq!(buffer, &[3]);
+
+        let mut out = [0u8; 2];
+        (&mut out[..]).encode(&value).unwrap();
+        assert_eq!(out, [1, 2]);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    buffer::{
+        BufferError, BufferErrorReason, FiniteBuffer, PositionedBuffer, Result, SplittableBuffer,
+    },
+    decode::{Decoder, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+
+/// Skips all of the remaining bytes, recording how many were skipped
+///
+/// Encoding writes the recorded number of `FILL` bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SkipAll<const FILL: u8 = 0>(pub usize);
+
+impl<B: FiniteBuffer, const FILL: u8> TypeDecoder<B> for SkipAll<FILL> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (slice, buffer) = buffer.consume();
+        let len = slice.len();
+        Ok((Self(len), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, const FILL: u8> TypeEncoder<B> for SkipAll<FILL> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_repeated(FILL, self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, const FILL: u8> TypeEncoder<B> for &SkipAll<FILL> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (*self).encode_type(buffer)
+    }
+}
+
+/// Skips the next `n` bytes
+///
+/// Encoding writes `n` zero bytes. Use `SkipFill` for a different filler.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SkipN(pub usize);
+
+impl<B: SplittableBuffer> Decoder<(), B> for SkipN {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<(), B> {
+        SkipFill::<0>(self.0).decode_from(buffer)
+    }
+}
+
+impl<B: EncoderBuffer> Encoder<(), B> for SkipN {
+    #[inline(always)]
+    fn encode_into(self, value: (), buffer: B) -> Result<(), B> {
+        SkipFill::<0>(self.0).encode_into(value, buffer)
+    }
+}
+
+/// Skips the next `n` bytes
+///
+/// Encoding writes `n` `FILL` bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SkipFill<const FILL: u8>(pub usize);
+
+impl<B: SplittableBuffer, const FILL: u8> Decoder<(), B> for SkipFill<FILL> {
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.checked_split(self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<B: EncoderBuffer, const FILL: u8> Encoder<(), B> for SkipFill<FILL> {
+    #[inline(always)]
+    fn encode_into(self, _value: (), buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_repeated(FILL, self.0)?;
+        Ok(((), buffer))
+    }
+}
+
+/// Skips bytes until the buffer reaches the given position
+///
+/// Encoding writes `FILL` bytes up to the position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SkipTo<const FILL: u8 = 0>(pub usize);
+
+impl<const FILL: u8> SkipTo<FILL> {
+    #[inline(always)]
+    fn skip_len<B: PositionedBuffer>(self, buffer: B) -> Result<usize, B> {
+        match self.0.checked_sub(buffer.position()) {
+            Some(len) => Ok((len, buffer)),
+            None => Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "skip target is behind the current position",
+                },
+            )),
+        }
+    }
+}
+
+impl<B, const FILL: u8> Decoder<(), B> for SkipTo<FILL>
+where
+    B: SplittableBuffer + PositionedBuffer,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<(), B> {
+        let (len, buffer) = self.skip_len(buffer)?;
+        SkipFill::<FILL>(len).decode_from(buffer)
+    }
+}
+
+impl<B, const FILL: u8> Encoder<(), B> for SkipTo<FILL>
+where
+    B: EncoderBuffer + PositionedBuffer,
+{
+    #[inline(always)]
+    fn encode_into(self, value: (), buffer: B) -> Result<(), B> {
+        let (len, buffer) = self.skip_len(buffer)?;
+        SkipFill::<FILL>(len).encode_into(value, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{buffer::Positioned, decode::DecoderBuffer};
+
+    #[test]
+    fn skip_all_test() {
+        let buffer = &[1, 2, 3][..];
+        let (value, buffer) = buffer.decode::<SkipAll>().unwrap();
+        assert_eq!(value, SkipAll(3));
+        assert!(buffer.is_empty());
+
+        let mut out = [0u8; 3];
+        (&mut out[..]).encode(SkipAll::<0xff>(3)).unwrap();
+        assert_eq!(out, [0xff; 3]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_skip_test() {
+        use crate::decode::Skip;
+
+        let buffer = &[1, 2, 3][..];
+        let (Skip(len), buffer) = buffer.decode::<Skip>().unwrap();
+        assert_eq!(len, 3);
+        assert!(buffer.is_empty());
+        assert_eq!(SkipAll::from(Skip(len)), SkipAll(3));
+    }
+
+    #[test]
+    fn skip_n_test() {
+        let buffer = &[1, 2, 3][..];
+        let ((), buffer) = buffer.decode_with(SkipN(2)).unwrap();
+        assert_eq!(buffer, &[3]);
+        assert!(buffer.decode_with(SkipN(2)).is_err());
+
+        let mut out = [1u8; 3];
+        let (_, rest) = (&mut out[..]).encode_with((), SkipN(2)).unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(out, [0, 0, 1]);
+    }
+
+    #[test]
+    fn skip_fill_test() {
+        let buffer = &[1, 2, 3][..];
+        let ((), buffer) = buffer.decode_with(SkipFill::<0xff>(2)).unwrap();
+        assert_eq!(buffer, &[3]);
+
+        let mut out = [1u8; 3];
+        (&mut out[..]).encode_with((), SkipFill::<0xff>(2)).unwrap();
+        assert_eq!(out, [0xff, 0xff, 1]);
+    }
+
+    #[test]
+    fn skip_to_test() {
+        let buffer = Positioned::new(&[1, 2, 3, 4][..]);
+        let (_, buffer) = buffer.decode::<u8>().unwrap();
+        let ((), buffer) = buffer.decode_with(SkipTo::<0>(3)).unwrap();
+        assert_eq!(buffer.position(), 3);
+        assert!(buffer.decode_with(SkipTo::<0>(2)).is_err());
+
+        let mut out = [0u8; 4];
+        let buffer = Positioned::new(&mut out[..]);
+        let (_, buffer) = buffer.encode(1u8).unwrap();
+        let (_, buffer) = buffer.encode_with((), SkipTo::<0xaa>(3)).unwrap();
+        assert_eq!(buffer.position(), 3);
+        assert_eq!(out, [1, 0xaa, 0xaa, 0]);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     buffer::{
-        BorrowedBuffer, BorrowedMutBuffer, BufferError, FiniteBuffer, FiniteMutBuffer, Result,
-        SplittableBuffer, SplittableMutBuffer,
+        BorrowedBuffer, BorrowedMutBuffer, FiniteBuffer, FiniteMutBuffer, Result, SplittableBuffer,
+        SplittableMutBuffer,
     },
     decode::TypeDecoder,
     encode::{EncoderBuffer, TypeEncoder},
@@ -109,14 +109,7 @@ impl EncoderBuffer for &mut [u8] {
                     let (_, buffer) = self.split_at_mut(consumed_len);
                     Ok((consumed_len, buffer))
                 }
-                Err(err) => {
-                    let reason = err.reason;
-                    drop(err);
-                    Err(BufferError {
-                        reason,
-                        buffer: self,
-                    })
-                }
+                Err(err) => Err(err.with_buffer(self)),
             }
         }
     }
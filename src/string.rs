@@ -0,0 +1,96 @@
+use crate::{
+    buffer::Result,
+    encode::{EncoderBuffer, TypeEncoder},
+};
+#[cfg(feature = "alloc")]
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer},
+    decode::{DecoderBuffer, TypeDecoder},
+};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// Decodes the remaining bytes as UTF-8
+#[cfg(feature = "alloc")]
+impl<B: FiniteBuffer> TypeDecoder<B> for String {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        buffer.decode_nested(|buffer| {
+            let len = buffer.len();
+            let ((), buffer) = buffer.reserve_alloc(len)?;
+
+            let value = match core::str::from_utf8(buffer.as_less_safe_slice()) {
+                Ok(value) => String::from(value),
+                Err(err) => {
+                    let valid_up_to = err.valid_up_to();
+                    return Err(BufferError::new(
+                        buffer,
+                        BufferErrorReason::InvalidUtf8 { valid_up_to },
+                    ));
+                }
+            };
+
+            let (_, buffer) = buffer.consume();
+            Ok((value, buffer))
+        })
+    }
+}
+
+impl<B: EncoderBuffer> TypeEncoder<B> for &str {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_bytes(self)?;
+        Ok(((), buffer))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<B: EncoderBuffer> TypeEncoder<B> for String {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.as_str().encode_type(buffer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<B: EncoderBuffer> TypeEncoder<B> for &String {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.as_str().encode_type(buffer)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::limit::{Limited, Limits};
+
+    #[test]
+    fn round_trip_test() {
+        let buffer = &b"hello"[..];
+        let (value, _) = buffer.decode::<String>().unwrap();
+        assert_eq!(value, "hello");
+
+        let mut out = [0u8; 5];
+        (&mut out[..]).encode(&value).unwrap();
+        assert_eq!(&out, b"hello");
+    }
+
+    #[test]
+    fn invalid_utf8_test() {
+        let buffer = &[b'a', 0xff][..];
+        let err = buffer.decode::<String>().unwrap_err();
+        assert_eq!(
+            err.reason,
+            BufferErrorReason::InvalidUtf8 { valid_up_to: 1 }
+        );
+    }
+
+    #[test]
+    fn limit_test() {
+        let limits = Limits::new().with_max_alloc(4);
+        let buffer = Limited::new(&b"hello"[..], &limits);
+        let err = buffer.decode::<String>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 4 });
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    buffer::{FiniteBuffer, Result, SplittableBuffer},
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, TypeEncoder},
+};
+
+/// Decodes a value from the next `n` bytes, discarding any leftovers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Take(pub usize);
+
+impl<T, B> Decoder<T, B> for Take
+where
+    B: SplittableBuffer,
+    T: TypeDecoder<B::Slice>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<T, B> {
+        let (slice, buffer) = buffer.checked_split(self.0)?;
+        let (value, buffer) =
+            map_buffer_error!(slice.decode_nested(|slice| slice.decode::<T>()), buffer);
+        Ok((value, buffer))
+    }
+}
+
+/// Decodes a value from the next `n` bytes, requiring all of them to be consumed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Exact(pub usize);
+
+impl<T, B> Decoder<T, B> for Exact
+where
+    B: SplittableBuffer,
+    T: TypeDecoder<B::Slice>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<T, B> {
+        let (slice, buffer) = buffer.checked_split(self.0)?;
+        let (value, buffer) = map_buffer_error!(
+            slice.decode_nested(|slice| slice.consumed_decode::<T>()),
+            buffer
+        );
+        Ok((value, buffer))
+    }
+}
+
+/// A value decoded from all of the remaining bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rest<T>(pub T);
+
+impl<T, B> TypeDecoder<B> for Rest<T>
+where
+    B: FiniteBuffer,
+    T: TypeDecoder<B>,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (slice, buffer) = buffer.consume();
+        let (value, buffer) = map_buffer_error!(
+            slice.decode_nested(|slice| slice.consumed_decode::<T>()),
+            buffer
+        );
+        Ok((Self(value), buffer))
+    }
+}
+
+impl<T: TypeEncoder<B>, B: EncoderBuffer> TypeEncoder<B> for Rest<T> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        self.0.encode_type(buffer)
+    }
+}
+
+impl<'a, T, B: EncoderBuffer> TypeEncoder<B> for &'a Rest<T>
+where
+    &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self.0).encode_type(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::BufferErrorReason;
+
+    #[test]
+    fn take_test() {
+        let buffer = &[0, 1, 2, 3, 4][..];
+        let (value, buffer) = buffer.decode_with(Take(3)).unwrap();
+        let value: u16 = value;
+        assert_eq!(value, 1);
+        assert_eq!(buffer, &[3, 4]);
+
+        assert!(buffer
+            .decode_with(Take(3))
+            .map(|(v, _): (u8, _)| v)
+            .is_err());
+    }
+
+    #[test]
+    fn exact_test() {
+        let buffer = &[0, 1, 2, 3][..];
+        let (value, buffer) = buffer.decode_with(Exact(2)).unwrap();
+        let value: u16 = value;
+        assert_eq!(value, 1);
+
+        let err = buffer
+            .decode_with(Exact(2))
+            .map(|(v, _): (u8, _)| v)
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::UnexpectedBytes { len: 1 });
+    }
+
+    #[test]
+    fn rest_test() {
+        let buffer = &[0, 1, 0, 2][..];
+        let (value, buffer) = buffer.decode::<(u16, Rest<u16>)>().unwrap();
+        assert_eq!(value, (1, Rest(2)));
+        assert!(buffer.is_empty());
+
+        let buffer = &[0, 1, 0][..];
+        assert!(buffer.decode::<Rest<u16>>().is_err());
+    }
+}
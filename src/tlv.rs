@@ -1,9 +1,13 @@
 use crate::{
-    buffer::{Result, SplittableBuffer},
+    buffer::{
+        BorrowedBuffer, BufferError, BufferErrorReason, FiniteBuffer, LookaheadBuffer, Result,
+        SplittableBuffer,
+    },
     decode::{Decoder, DecoderBuffer, TypeDecoder},
     encode::{Encoder, EncoderBuffer, TypeEncoder},
-    len::LenPrefix,
+    len::{Exclusive, LenPrefix, LenPrefixWith},
 };
+use core::{convert::TryInto, marker::PhantomData};
 
 pub struct TLV<T, L, V> {
     pub tag: T,
@@ -51,3 +55,644 @@ where
         Ok(((), buffer))
     }
 }
+
+/// Decodes and encodes the value of a `TLV` with a custom codec, such as a closure
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TlvWith<D>(pub D);
+
+impl<B, T, L, V, D> Decoder<TLV<T, L, V>, B> for TlvWith<D>
+where
+    B: FiniteBuffer,
+    T: TypeDecoder<B>,
+    LenPrefixWith<L, Exclusive, D>: Decoder<V, B>,
+{
+    fn decode_from(self, buffer: B) -> Result<TLV<T, L, V>, B> {
+        let (tag, buffer) = buffer.decode()?;
+        let len = LenPrefix::new::<L>();
+        let (value, buffer) = buffer.decode_with(len.with(self.0))?;
+
+        Ok((TLV { tag, len, value }, buffer))
+    }
+}
+
+impl<'a, B, T, L, V, D> Encoder<&'a TLV<T, L, V>, B> for TlvWith<D>
+where
+    B: EncoderBuffer,
+    &'a T: TypeEncoder<B>,
+    LenPrefixWith<L, Exclusive, D>: Encoder<&'a V, B>,
+{
+    fn encode_into(self, tlv: &'a TLV<T, L, V>, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(&tlv.tag)?;
+        let (_, buffer) = buffer.encode_with(&tlv.value, tlv.len.with(self.0))?;
+        Ok(((), buffer))
+    }
+}
+
+/// A single TLV with its value left undecoded
+///
+/// Encoding writes the tag, the original length, and the value bytes verbatim so
+/// unknown TLVs can be passed through unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawTlv<T, L, S> {
+    pub tag: T,
+    pub len: L,
+    pub value: S,
+}
+
+impl<T, L, S: FiniteBuffer> RawTlv<T, L, S> {
+    /// Decodes the value, requiring all of its bytes to be consumed
+    #[inline(always)]
+    pub fn decode_value<V: TypeDecoder<S>>(self) -> Result<V, S> {
+        self.value.consumed_decode()
+    }
+
+    /// Decodes the value with `decoder`, requiring all of its bytes to be consumed
+    #[inline(always)]
+    pub fn decode_value_with<V, D: Decoder<V, S>>(self, decoder: D) -> Result<V, S> {
+        let (value, buffer) = self.value.decode_with(decoder)?;
+        let ((), buffer) = buffer.ensure_empty()?;
+        Ok((value, buffer))
+    }
+}
+
+impl<B, T, L> TypeDecoder<B> for RawTlv<T, L, B::Slice>
+where
+    B: SplittableBuffer,
+    T: TypeDecoder<B>,
+    L: TypeDecoder<B> + TryInto<usize> + Copy,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let (tag, buffer) = buffer.decode()?;
+        let (len, buffer) = buffer.decode::<L>()?;
+        let value_len = match len.try_into() {
+            Ok(value_len) => value_len,
+            Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+        let (value, buffer) = buffer.checked_split(value_len)?;
+        Ok((Self { tag, len, value }, buffer))
+    }
+}
+
+impl<B, T, L, S> TypeEncoder<B> for RawTlv<T, L, S>
+where
+    B: EncoderBuffer,
+    T: TypeEncoder<B>,
+    L: TypeEncoder<B>,
+    S: FiniteBuffer,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(self.tag)?;
+        let (_, buffer) = buffer.encode(self.len)?;
+        let (_, buffer) = buffer.encode_bytes(self.value.as_less_safe_slice())?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, B, T, L, S> TypeEncoder<B> for &'a RawTlv<T, L, S>
+where
+    B: EncoderBuffer,
+    &'a T: TypeEncoder<B>,
+    &'a L: TypeEncoder<B>,
+    S: FiniteBuffer,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(&self.tag)?;
+        let (_, buffer) = buffer.encode(&self.len)?;
+        let (_, buffer) = buffer.encode_bytes(self.value.as_less_safe_slice())?;
+        Ok(((), buffer))
+    }
+}
+
+/// Iterates over a sequence of TLVs without decoding their values
+///
+/// Iteration stops at the end of the buffer or after the first error.
+#[derive(Debug)]
+pub struct TlvStream<T, L, B> {
+    buffer: Option<B>,
+    entry: PhantomData<(T, L)>,
+}
+
+impl<T, L, B> TlvStream<T, L, B> {
+    #[inline(always)]
+    pub fn new(buffer: B) -> Self {
+        Self {
+            buffer: Some(buffer),
+            entry: PhantomData,
+        }
+    }
+}
+
+impl<T, L, B> Iterator for TlvStream<T, L, B>
+where
+    B: FiniteBuffer,
+    T: TypeDecoder<B>,
+    L: TypeDecoder<B> + TryInto<usize> + Copy,
+{
+    type Item = core::result::Result<RawTlv<T, L, B>, BufferError<B>>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = self.buffer.take()?;
+
+        if buffer.is_empty() {
+            return None;
+        }
+
+        match buffer.decode() {
+            Ok((entry, buffer)) => {
+                self.buffer = Some(buffer);
+                Some(Ok(entry))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// A zero-copy view of a single TLV in a borrowed buffer
+///
+/// The value is only decoded when requested and encoding writes the original
+/// bytes verbatim.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TlvRef<'a, T, L> {
+    pub tag: T,
+    pub len: L,
+    bytes: &'a [u8],
+    header_len: usize,
+}
+
+impl<'a, T, L> TlvRef<'a, T, L> {
+    /// Returns the encoded bytes of the entire TLV
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the raw value bytes
+    #[inline(always)]
+    pub fn value_bytes(&self) -> &'a [u8] {
+        &self.bytes[self.header_len..]
+    }
+
+    /// Decodes the value, requiring all of its bytes to be consumed
+    #[inline(always)]
+    pub fn value<V: TypeDecoder<&'a [u8]>>(&self) -> Result<V, &'a [u8]> {
+        self.value_bytes().consumed_decode()
+    }
+
+    /// Decodes the value with `decoder`, requiring all of its bytes to be consumed
+    #[inline(always)]
+    pub fn value_with<V, D: Decoder<V, &'a [u8]>>(&self, decoder: D) -> Result<V, &'a [u8]> {
+        let (value, buffer) = self.value_bytes().decode_with(decoder)?;
+        let ((), buffer) = buffer.ensure_empty()?;
+        Ok((value, buffer))
+    }
+}
+
+impl<'a, B, T, L> TypeDecoder<B> for TlvRef<'a, T, L>
+where
+    B: BorrowedBuffer<'a>,
+    T: for<'b> TypeDecoder<LookaheadBuffer<'b>>,
+    L: for<'b> TypeDecoder<LookaheadBuffer<'b>> + TryInto<usize> + Copy,
+{
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        let header = match buffer.peek::<(T, L)>() {
+            Ok(((tag, len), rest)) => Ok((tag, len, buffer.len() - rest.len())),
+            Err(err) => Err(err.with_buffer(())),
+        };
+        let (tag, len, header_len) = match header {
+            Ok(header) => header,
+            Err(err) => return Err(err.with_buffer(buffer)),
+        };
+
+        let value_len = match len.try_into() {
+            Ok(value_len) => value_len,
+            Err(_) => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+        let total_len = match header_len.checked_add(value_len) {
+            Some(total_len) => total_len,
+            None => return Err(BufferError::new(buffer, BufferErrorReason::LengthOverflow)),
+        };
+
+        let (bytes, buffer) = buffer.checked_split(total_len)?;
+        let value = Self {
+            tag,
+            len,
+            bytes: bytes.into_less_safe_slice(),
+            header_len,
+        };
+        Ok((value, buffer))
+    }
+}
+
+impl<'a, B: EncoderBuffer, T, L> TypeEncoder<B> for TlvRef<'a, T, L> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_bytes(self.bytes)?;
+        Ok(((), buffer))
+    }
+}
+
+impl<'a, B: EncoderBuffer, T, L> TypeEncoder<B> for &TlvRef<'a, T, L> {
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode_bytes(self.bytes)?;
+        Ok(((), buffer))
+    }
+}
+
+/// A set of TLV value types, keyed by tag
+///
+/// This is usually implemented with the `tlv_registry!` macro.
+pub trait TlvRegistry<S: FiniteBuffer>: Sized {
+    type Tag: PartialEq + 'static;
+    type Len;
+
+    /// The known tags, in declaration order
+    const TAGS: &'static [Self::Tag];
+
+    /// Whether each of the known tags must be present
+    const REQUIRED: &'static [bool];
+
+    /// Decodes the value for a known tag
+    fn decode_value(tag: &Self::Tag, value: S) -> Result<Self, S>;
+
+    /// Returns the tag for the value
+    fn tag(&self) -> Self::Tag;
+}
+
+/// How to handle tags that are not in the registry
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UnknownTags {
+    #[default]
+    Skip,
+    Reject,
+    Preserve,
+}
+
+/// How to handle a tag that appears more than once
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DuplicateTags {
+    #[default]
+    Allow,
+    Reject,
+}
+
+/// A TLV yielded by `TlvDispatch`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dispatched<E, R> {
+    Known(E),
+    Unknown(R),
+}
+
+/// Decodes each TLV in a buffer into the registry type `E`
+///
+/// Once the buffer is exhausted, an error is yielded if any of the registry's
+/// required tags were not seen.
+#[derive(Debug)]
+pub struct TlvDispatch<E, B> {
+    buffer: Option<B>,
+    unknown: UnknownTags,
+    duplicates: DuplicateTags,
+    seen: u64,
+    registry: PhantomData<E>,
+}
+
+impl<E, B> TlvDispatch<E, B> {
+    #[inline(always)]
+    pub fn new(buffer: B) -> Self {
+        Self {
+            buffer: Some(buffer),
+            unknown: UnknownTags::default(),
+            duplicates: DuplicateTags::default(),
+            seen: 0,
+            registry: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub fn with_unknown(mut self, unknown: UnknownTags) -> Self {
+        self.unknown = unknown;
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_duplicates(mut self, duplicates: DuplicateTags) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+}
+
+impl<E, B> Iterator for TlvDispatch<E, B>
+where
+    B: FiniteBuffer,
+    E: TlvRegistry<B>,
+    E::Tag: TypeDecoder<B> + Copy + Into<u64>,
+    E::Len: TypeDecoder<B> + TryInto<usize> + Copy,
+{
+    type Item = core::result::Result<Dispatched<E, RawTlv<E::Tag, E::Len, B>>, BufferError<B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = self.buffer.take()?;
+
+        loop {
+            if buffer.is_empty() {
+                let missing = E::REQUIRED
+                    .iter()
+                    .enumerate()
+                    .position(|(index, required)| *required && self.seen & (1 << index) == 0);
+
+                if let Some(index) = missing {
+                    let tag = E::TAGS[index].into();
+                    return Some(Err(BufferError::new(
+                        buffer,
+                        BufferErrorReason::MissingTag { tag },
+                    )));
+                }
+
+                return None;
+            }
+
+            let (raw, rest) = match buffer.decode::<RawTlv<E::Tag, E::Len, B>>() {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err)),
+            };
+
+            let index = match E::TAGS.iter().position(|tag| *tag == raw.tag) {
+                Some(index) => index,
+                None => match self.unknown {
+                    UnknownTags::Skip => {
+                        buffer = rest;
+                        continue;
+                    }
+                    UnknownTags::Reject => {
+                        let tag: u64 = raw.tag.into();
+                        return Some(Err(BufferError::new(
+                            rest,
+                            BufferErrorReason::UnknownTag { tag: tag.into() },
+                        )));
+                    }
+                    UnknownTags::Preserve => {
+                        self.buffer = Some(rest);
+                        return Some(Ok(Dispatched::Unknown(raw)));
+                    }
+                },
+            };
+
+            let mask = 1 << index;
+            if self.duplicates == DuplicateTags::Reject && self.seen & mask != 0 {
+                let tag = raw.tag.into();
+                return Some(Err(BufferError::new(
+                    rest,
+                    BufferErrorReason::DuplicateTag { tag },
+                )));
+            }
+            self.seen |= mask;
+
+            return match E::decode_value(&raw.tag, raw.value) {
+                Ok((value, _)) => {
+                    self.buffer = Some(rest);
+                    Some(Ok(Dispatched::Known(value)))
+                }
+                Err(err) => Some(Err(err.with_buffer(rest))),
+            };
+        }
+    }
+}
+
+/// Declares an enum of TLV values and implements `TlvRegistry` for it
+///
+/// ```
+/// codec::tlv_registry! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Attribute: (u8, u8) {
+///         #[required]
+///         1 => Kind(u8),
+///         2 => Port(u16),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! tlv_registry {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident: ($tag:ty, $len:ty) {
+            $(
+                $(#[$attr:ident])*
+                $value:literal => $variant:ident($ty:ty)
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $variant($ty),
+            )*
+        }
+
+        impl<S: $crate::buffer::FiniteBuffer> $crate::tlv::TlvRegistry<S> for $name
+        where
+            $($ty: $crate::decode::TypeDecoder<S>,)*
+        {
+            type Tag = $tag;
+            type Len = $len;
+
+            const TAGS: &'static [$tag] = &[$($value),*];
+            const REQUIRED: &'static [bool] = &[
+                $(false $(|| $crate::__tlv_registry_attr!($attr))*),*
+            ];
+
+            #[inline(always)]
+            fn decode_value(tag: &$tag, value: S) -> $crate::buffer::Result<Self, S> {
+                match *tag {
+                    $(
+                        $value => {
+                            let (value, buffer) = $crate::buffer::FiniteBuffer::consumed_decode(value)?;
+                            Ok((Self::$variant(value), buffer))
+                        }
+                    )*
+                    _ => {
+                        let tag = *tag as u128;
+                        Err($crate::buffer::BufferError::new(
+                            value,
+                            $crate::buffer::BufferErrorReason::UnknownTag { tag },
+                        ))
+                    }
+                }
+            }
+
+            #[inline(always)]
+            fn tag(&self) -> $tag {
+                match self {
+                    $(
+                        Self::$variant(_) => $value,
+                    )*
+                }
+            }
+        }
+
+        impl<'a, B> $crate::encode::TypeEncoder<B> for &'a $name
+        where
+            B: $crate::encode::EncoderBuffer,
+            $tag: $crate::encode::TypeEncoder<B>,
+            $($crate::len::LenPrefix<$len>: $crate::encode::Encoder<&'a $ty, B>,)*
+        {
+            #[inline(always)]
+            fn encode_type(self, buffer: B) -> $crate::buffer::Result<(), B> {
+                match self {
+                    $(
+                        $name::$variant(value) => {
+                            let (_, buffer) =
+                                $crate::encode::EncoderBuffer::encode::<$tag>(buffer, $value)?;
+                            let (_, buffer) = $crate::encode::EncoderBuffer::encode_with(
+                                buffer,
+                                value,
+                                $crate::len::LenPrefix::new::<$len>(),
+                            )?;
+                            Ok(((), buffer))
+                        }
+                    )*
+                }
+            }
+        }
+
+        const _: () = assert!(
+            <[$tag]>::len(&[$($value),*]) <= 64,
+            "a TLV registry supports at most 64 tags"
+        );
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __tlv_registry_attr {
+    (required) => {
+        true
+    };
+    ($other:ident) => {
+        compile_error!(concat!(
+            "unknown TLV registry attribute: ",
+            stringify!($other)
+        ))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_test() {
+        let buffer = &[1, 2, 0, 7, 9, 1, 3, 2, 5, 0][..];
+        let mut stream = TlvStream::<u8, u8, _>::new(buffer);
+
+        let known = stream.next().unwrap().unwrap();
+        assert_eq!(known.tag, 1);
+        let (value, _) = known.decode_value::<u16>().unwrap();
+        assert_eq!(value, 7);
+
+        let unknown = stream.next().unwrap().unwrap();
+        assert_eq!(unknown.tag, 9);
+        assert_eq!(unknown.value, &[3]);
+
+        // the stream stops at the first malformed entry
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+
+        let mut out = [0u8; 3];
+        (&mut out[..]).encode(unknown).unwrap();
+        assert_eq!(out, [9, 1, 3]);
+    }
+
+    #[test]
+    fn ref_test() {
+        let buffer = &[1, 2, 0, 7, 9][..];
+        let (entry, buffer) = buffer.decode::<TlvRef<u8, u8>>().unwrap();
+        assert_eq!(entry.tag, 1);
+        assert_eq!(entry.len, 2);
+        assert_eq!(entry.value_bytes(), &[0, 7]);
+        assert_eq!(entry.as_bytes(), &[1, 2, 0, 7]);
+        assert_eq!(buffer, &[9]);
+
+        let (value, _) = entry.value::<u16>().unwrap();
+        assert_eq!(value, 7);
+        assert!(entry.value::<u8>().is_err());
+
+        let mut out = [0u8; 4];
+        (&mut out[..]).encode(entry).unwrap();
+        assert_eq!(out, [1, 2, 0, 7]);
+
+        let truncated = &[1, 2, 0][..];
+        assert!(truncated.decode::<TlvRef<u8, u8>>().is_err());
+    }
+
+    tlv_registry! {
+        #[derive(Debug, PartialEq)]
+        enum Attribute: (u8, u8) {
+            #[required]
+            1 => Kind(u8),
+            2 => Port(u16),
+        }
+    }
+
+    type Dispatch<'a> = TlvDispatch<Attribute, &'a [u8]>;
+
+    #[test]
+    fn dispatch_test() {
+        let buffer = &[2, 2, 0, 80, 9, 1, 0, 1, 1, 4][..];
+        let values: Vec<_> = Dispatch::new(buffer)
+            .collect::<core::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            values,
+            [
+                Dispatched::Known(Attribute::Port(80)),
+                Dispatched::Known(Attribute::Kind(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn preserve_unknown_test() {
+        let buffer = &[9, 1, 0, 1, 1, 4][..];
+        let mut dispatch = Dispatch::new(buffer).with_unknown(UnknownTags::Preserve);
+        match dispatch.next().unwrap().unwrap() {
+            Dispatched::Unknown(raw) => assert_eq!(raw.tag, 9),
+            other => panic!("unexpected entry {:?}", other),
+        }
+    }
+
+    #[test]
+    fn policy_errors_test() {
+        let unknown = &[9, 0][..];
+        let err = Dispatch::new(unknown)
+            .with_unknown(UnknownTags::Reject)
+            .find_map(|entry| entry.err())
+            .unwrap();
+        assert_eq!(err.reason, BufferErrorReason::UnknownTag { tag: 9 });
+
+        let duplicate = &[1, 1, 4, 1, 1, 5][..];
+        assert!(Dispatch::new(duplicate).all(|entry| entry.is_ok()));
+        let err = Dispatch::new(duplicate)
+            .with_duplicates(DuplicateTags::Reject)
+            .find_map(|entry| entry.err())
+            .unwrap();
+        assert_eq!(err.reason, BufferErrorReason::DuplicateTag { tag: 1 });
+
+        let missing = &[2, 2, 0, 80][..];
+        let err = Dispatch::new(missing)
+            .find_map(|entry| entry.err())
+            .unwrap();
+        assert_eq!(err.reason, BufferErrorReason::MissingTag { tag: 1 });
+    }
+
+    #[test]
+    fn encode_test() {
+        let mut out = [0u8; 4];
+        (&mut out[..]).encode(&Attribute::Port(80)).unwrap();
+        assert_eq!(out, [2, 2, 0, 80]);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, Result, SplittableBuffer},
+    constant::ConstValue,
+    decode::{Decoder, DecoderBuffer, TypeDecoder},
+    encode::{Encoder, EncoderBuffer, TypeEncoder},
+};
+use core::marker::PhantomData;
+
+/// Discriminants encoded as a `T`, with `FIRST` selecting the first variant and `SECOND`
+/// selecting the second
+///
+/// The tags are given as `i128` so signed types can use negative values. They must be in
+/// range for `T`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tags<T, const FIRST: i128 = 0, const SECOND: i128 = 1>(PhantomData<T>);
+
+impl<T, const FIRST: i128, const SECOND: i128> Clone for Tags<T, FIRST, SECOND> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const FIRST: i128, const SECOND: i128> Copy for Tags<T, FIRST, SECOND> {}
+
+impl<T, const FIRST: i128, const SECOND: i128> Default for Tags<T, FIRST, SECOND> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, const FIRST: i128, const SECOND: i128> Tags<T, FIRST, SECOND> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// Decodes the discriminant, returning `true` for the second variant
+    #[inline(always)]
+    fn decode_tag<B>(buffer: B) -> Result<bool, B>
+    where
+        B: SplittableBuffer,
+        T: TypeDecoder<B> + ConstValue,
+    {
+        let (tag, buffer) = buffer.decode::<T>()?;
+        if T::from_i128(FIRST) == Some(tag) {
+            Ok((false, buffer))
+        } else if T::from_i128(SECOND) == Some(tag) {
+            Ok((true, buffer))
+        } else {
+            Err(BufferError::new(
+                buffer,
+                BufferErrorReason::UnknownTag { tag: tag.to_bits() },
+            ))
+        }
+    }
+
+    #[inline(always)]
+    fn encode_tag<B>(second: bool, buffer: B) -> Result<(), B>
+    where
+        B: EncoderBuffer,
+        T: TypeEncoder<B> + ConstValue,
+    {
+        let tag = if second { SECOND } else { FIRST };
+        match T::from_i128(tag) {
+            Some(tag) => {
+                let (_, buffer) = buffer.encode(tag)?;
+                Ok(((), buffer))
+            }
+            None => Err(BufferError::new(
+                buffer,
+                BufferErrorReason::InvalidValue {
+                    message: "tag out of range",
+                },
+            )),
+        }
+    }
+}
+
+/// One of two values
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<A, B> {
+    First(A),
+    Second(B),
+}
+
+impl<A, B> From<core::result::Result<A, B>> for Either<A, B> {
+    #[inline(always)]
+    fn from(value: core::result::Result<A, B>) -> Self {
+        match value {
+            Ok(value) => Self::First(value),
+            Err(value) => Self::Second(value),
+        }
+    }
+}
+
+impl<A, B> From<Either<A, B>> for core::result::Result<A, B> {
+    #[inline(always)]
+    fn from(value: Either<A, B>) -> Self {
+        match value {
+            Either::First(value) => Ok(value),
+            Either::Second(value) => Err(value),
+        }
+    }
+}
+
+/// One of two values, preceded by a discriminant described by `Tag`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tagged<Tag, A, B> {
+    pub value: Either<A, B>,
+    tag: PhantomData<Tag>,
+}
+
+impl<Tag, A, B> Tagged<Tag, A, B> {
+    #[inline(always)]
+    pub const fn new(value: Either<A, B>) -> Self {
+        Self {
+            value,
+            tag: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn first(value: A) -> Self {
+        Self::new(Either::First(value))
+    }
+
+    #[inline(always)]
+    pub const fn second(value: B) -> Self {
+        Self::new(Either::Second(value))
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> Either<A, B> {
+        self.value
+    }
+}
+
+impl<T, A, B, Buf, const FIRST: i128, const SECOND: i128> TypeDecoder<Buf>
+    for Tagged<Tags<T, FIRST, SECOND>, A, B>
+where
+    Buf: SplittableBuffer,
+    T: TypeDecoder<Buf> + ConstValue,
+    A: TypeDecoder<Buf>,
+    B: TypeDecoder<Buf>,
+{
+    #[inline(always)]
+    fn decode_type(buffer: Buf) -> Result<Self, Buf> {
+        let (second, buffer) = Tags::<T, FIRST, SECOND>::decode_tag(buffer)?;
+        if second {
+            let (value, buffer) = buffer.decode()?;
+            Ok((Self::second(value), buffer))
+        } else {
+            let (value, buffer) = buffer.decode()?;
+            Ok((Self::first(value), buffer))
+        }
+    }
+}
+
+impl<T, A, B, Buf, const FIRST: i128, const SECOND: i128> TypeEncoder<Buf>
+    for Tagged<Tags<T, FIRST, SECOND>, A, B>
+where
+    Buf: EncoderBuffer,
+    T: TypeEncoder<Buf> + ConstValue,
+    A: TypeEncoder<Buf>,
+    B: TypeEncoder<Buf>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: Buf) -> Result<(), Buf> {
+        match self.value {
+            Either::First(value) => {
+                let (_, buffer) = Tags::<T, FIRST, SECOND>::encode_tag(false, buffer)?;
+                let (_, buffer) = buffer.encode(value)?;
+                Ok(((), buffer))
+            }
+            Either::Second(value) => {
+                let (_, buffer) = Tags::<T, FIRST, SECOND>::encode_tag(true, buffer)?;
+                let (_, buffer) = buffer.encode(value)?;
+                Ok(((), buffer))
+            }
+        }
+    }
+}
+
+impl<'a, T, A, B, Buf, const FIRST: i128, const SECOND: i128> TypeEncoder<Buf>
+    for &'a Tagged<Tags<T, FIRST, SECOND>, A, B>
+where
+    Buf: EncoderBuffer,
+    T: TypeEncoder<Buf> + ConstValue,
+    &'a A: TypeEncoder<Buf>,
+    &'a B: TypeEncoder<Buf>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: Buf) -> Result<(), Buf> {
+        match &self.value {
+            Either::First(value) => {
+                let (_, buffer) = Tags::<T, FIRST, SECOND>::encode_tag(false, buffer)?;
+                let (_, buffer) = buffer.encode(value)?;
+                Ok(((), buffer))
+            }
+            Either::Second(value) => {
+                let (_, buffer) = Tags::<T, FIRST, SECOND>::encode_tag(true, buffer)?;
+                let (_, buffer) = buffer.encode(value)?;
+                Ok(((), buffer))
+            }
+        }
+    }
+}
+
+impl<T, A, E, B, const FIRST: i128, const SECOND: i128> Decoder<core::result::Result<A, E>, B>
+    for Tags<T, FIRST, SECOND>
+where
+    B: SplittableBuffer,
+    T: TypeDecoder<B> + ConstValue,
+    A: TypeDecoder<B>,
+    E: TypeDecoder<B>,
+{
+    #[inline(always)]
+    fn decode_from(self, buffer: B) -> Result<core::result::Result<A, E>, B> {
+        let (value, buffer) = buffer.decode::<Tagged<Self, A, E>>()?;
+        Ok((value.value.into(), buffer))
+    }
+}
+
+impl<T, A, E, B, const FIRST: i128, const SECOND: i128> Encoder<core::result::Result<A, E>, B>
+    for Tags<T, FIRST, SECOND>
+where
+    B: EncoderBuffer,
+    T: TypeEncoder<B> + ConstValue,
+    A: TypeEncoder<B>,
+    E: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_into(self, value: core::result::Result<A, E>, buffer: B) -> Result<(), B> {
+        let (_, buffer) = buffer.encode(Tagged::<Self, A, E>::new(value.into()))?;
+        Ok(((), buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn result_test() {
+        type Response = core::result::Result<u16, u8>;
+
+        let buffer = &[0u8, 0, 7, 1, 3, 2][..];
+        let (value, buffer) = buffer.decode::<Response>().unwrap();
+        assert_eq!(value, Ok(7));
+        let (value, buffer) = buffer.decode::<Response>().unwrap();
+        assert_eq!(value, Err(3));
+        let err = buffer.decode::<Response>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::UnknownTag { tag: 2 });
+
+        let mut out = [0u8; 2];
+        (&mut out[..]).encode(Response::Err(9)).unwrap();
+        assert_eq!(out, [1, 9]);
+    }
+
+    #[test]
+    fn custom_tags_test() {
+        type Status = Tags<u8, 0x80, 0x40>;
+
+        let buffer = &[0x40u8, 5][..];
+        let (value, _) = buffer
+            .decode_with::<core::result::Result<(), u8>, _>(Status::new())
+            .unwrap();
+        assert_eq!(value, Err(5));
+
+        let mut out = [0u8; 1];
+        (&mut out[..])
+            .encode_with(core::result::Result::<(), u8>::Ok(()), Status::new())
+            .unwrap();
+        assert_eq!(out, [0x80]);
+    }
+
+    #[test]
+    fn signed_tags_test() {
+        type Signed = Tags<i8, -1, 1>;
+
+        let buffer = &[0xffu8, 5, 0xfe][..];
+        let (value, buffer) = buffer
+            .decode_with::<core::result::Result<u8, ()>, _>(Signed::new())
+            .unwrap();
+        assert_eq!(value, Ok(5));
+        let err = buffer
+            .decode_with::<core::result::Result<u8, ()>, _>(Signed::new())
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::UnknownTag { tag: 0xfe });
+
+        let mut out = [0u8; 2];
+        (&mut out[..])
+            .encode_with(core::result::Result::<u8, ()>::Ok(5), Signed::new())
+            .unwrap();
+        assert_eq!(out, [0xff, 5]);
+    }
+
+    #[test]
+    fn wide_tags_test() {
+        let mut input = [0u8; 16];
+        input[0] = 0x80;
+        let err = (&input[..])
+            .decode_with::<core::result::Result<(), ()>, _>(Tags::<u128>::new())
+            .unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::UnknownTag { tag: 1 << 127 });
+    }
+
+    #[test]
+    fn nested_test() {
+        type Inner = Tagged<Tags<u8, 1, 2>, u8, u16>;
+        type Outer = Tagged<Tags<u8>, Inner, (u8, u8)>;
+
+        let buffer = &[0u8, 2, 0, 9][..];
+        let (value, buffer) = buffer.decode::<Outer>().unwrap();
+        assert_eq!(value, Outer::first(Inner::second(9)));
+        assert!(buffer.is_empty());
+
+        let mut out = [0u8; 4];
+        (&mut out[..]).encode(value).unwrap();
+        assert_eq!(out, [0, 2, 0, 9]);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    buffer::{BufferError, BufferErrorReason, FiniteBuffer, Result},
+    decode::{DecoderBuffer, TypeDecoder},
+    encode::{EncoderBuffer, TypeEncoder},
+};
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+/// Decodes items until the buffer is empty
+impl<B: FiniteBuffer, T: TypeDecoder<B>> TypeDecoder<B> for Vec<T> {
+    #[inline(always)]
+    fn decode_type(buffer: B) -> Result<Self, B> {
+        buffer.decode_nested(|mut buffer| {
+            let mut values = Vec::new();
+
+            while !buffer.is_empty() {
+                let ((), next) = buffer.ensure_sequence_len(values.len() + 1)?;
+                let ((), next) = next.reserve_alloc(size_of::<T>())?;
+                let len = next.len();
+                let (value, next) = next.decode()?;
+
+                // make sure we're making progress to avoid looping forever
+                if next.len() == len {
+                    return Err(BufferError::new(
+                        next,
+                        BufferErrorReason::InvalidValue {
+                            message: "sequence item consumed no bytes",
+                        },
+                    ));
+                }
+
+                values.push(value);
+                buffer = next;
+            }
+
+            Ok((values, buffer))
+        })
+    }
+}
+
+impl<B: EncoderBuffer, T> TypeEncoder<B> for Vec<T>
+where
+    for<'a> &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self[..]).encode_type(buffer)
+    }
+}
+
+impl<B: EncoderBuffer, T> TypeEncoder<B> for &Vec<T>
+where
+    for<'a> &'a T: TypeEncoder<B>,
+{
+    #[inline(always)]
+    fn encode_type(self, buffer: B) -> Result<(), B> {
+        (&self[..]).encode_type(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        len::LenPrefix,
+        limit::{Limited, Limits},
+    };
+
+    #[test]
+    fn round_trip_test() {
+        let buffer = &[0, 1, 0, 2, 0, 3][..];
+        let (value, _) = buffer.decode::<Vec<u16>>().unwrap();
+        assert_eq!(value, [1, 2, 3]);
+
+        let mut out = [0u8; 7];
+        (&mut out[..])
+            .encode_with(&value, LenPrefix::new::<u8>())
+            .unwrap();
+        assert_eq!(out, [6, 0, 1, 0, 2, 0, 3]);
+    }
+
+    #[test]
+    fn limit_test() {
+        let limits = Limits::new().with_max_sequence_len(2);
+        let buffer = Limited::new(&[0, 1, 0, 2, 0, 3][..], &limits);
+        let err = buffer.decode::<Vec<u16>>().unwrap_err();
+        assert_eq!(err.reason, BufferErrorReason::LimitExceeded { limit: 2 });
+    }
+}
@@ -78,10 +78,7 @@ macro_rules! impl_ref {
                 $(
                     {
                         if let Err(err) = owner.lookahead().$ensure_alignment::<T>() {
-                            return Err($crate::buffer::BufferError {
-                                reason: err.reason,
-                                buffer,
-                            });
+                            return Err(err.with_buffer(buffer));
                         }
                     }
                 )*